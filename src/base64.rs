@@ -0,0 +1,78 @@
+//! Base64 decoding that tolerates the formatting found in real books:
+//! line breaks and indentation inside the content, missing or excessive padding, padding
+//! in the middle of concatenated contents, and the URL-safe alphabet.
+
+use crate::Error;
+
+/// Marks a byte that is not a part of the alphabet
+const INVALID: u8 = 0xFF;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const DECODE_TABLE: [u8; 256] = {
+    let alphabet = ALPHABET;
+    let mut table = [INVALID; 256];
+    let mut i = 0;
+    while i < alphabet.len() {
+        table[alphabet[i] as usize] = i as u8;
+        i += 1;
+    }
+    table[b'-' as usize] = 62;
+    table[b'_' as usize] = 63;
+    table
+};
+
+pub(crate) fn decode(input: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut output = Vec::with_capacity(input.len() / 4 * 3);
+    let mut accumulator = 0u32;
+    let mut bits = 0;
+    let mut padding = false;
+    for (i, &byte) in input.iter().enumerate() {
+        if byte.is_ascii_whitespace() {
+            continue;
+        }
+        if byte == b'=' {
+            padding = true;
+            continue;
+        }
+        let value = DECODE_TABLE[byte as usize];
+        if padding {
+            // the padded content is over, the rest is another one
+            padding = false;
+            accumulator = 0;
+            bits = 0;
+        }
+        if value == INVALID {
+            return Err(Error::Binary(format!(
+                "unexpected byte {:?} at {i} in base64",
+                byte as char
+            )));
+        }
+        accumulator = accumulator << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((accumulator >> bits) as u8);
+            accumulator &= (1 << bits) - 1;
+        }
+    }
+    Ok(output)
+}
+
+/// Encodes with the standard alphabet and padding, in a single line
+pub(crate) fn encode(input: &[u8]) -> String {
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | (byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(ALPHABET[(group >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
@@ -0,0 +1,160 @@
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+
+use crate::{Binary, Error};
+
+/// A binary that is not loaded into memory, only its location in the source is known.
+/// See [`from_reader_lazy`](crate::from_reader_lazy)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LazyBinary {
+    pub id: String,
+    pub content_type: String,
+    /// Byte range of the base64 content in the source, relative to the start of the reader
+    pub range: Range<u64>,
+}
+
+impl LazyBinary {
+    /// Reads the content from the source the book was parsed from, and decodes it
+    pub fn read<R: Read + Seek>(&self, source: &mut R) -> Result<Vec<u8>, Error> {
+        source.seek(SeekFrom::Start(self.range.start))?;
+        let mut content = Vec::with_capacity(self.len() as usize);
+        source.take(self.len()).read_to_end(&mut content)?;
+        if content.len() as u64 != self.len() {
+            return Err(Error::Io(std::io::ErrorKind::UnexpectedEof.into()));
+        }
+        crate::base64::decode(&content)
+    }
+
+    /// Decodes the content from the whole source document, e.g. a memory map of the file
+    pub fn decode(&self, source: &[u8]) -> Result<Vec<u8>, Error> {
+        let content = usize::try_from(self.range.start)
+            .ok()
+            .zip(usize::try_from(self.range.end).ok())
+            .and_then(|(start, end)| source.get(start..end))
+            .ok_or_else(|| Error::Io(std::io::ErrorKind::UnexpectedEof.into()))?;
+        crate::base64::decode(content)
+    }
+
+    /// Length of the base64 content in bytes
+    pub fn len(&self) -> u64 {
+        self.range.end - self.range.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+}
+
+/// Format of an image, told by its first bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Gif,
+    WebP,
+    Svg,
+}
+
+impl ImageFormat {
+    /// Recognizes the format by the magic bytes, or by the root element of an SVG
+    pub fn sniff(bytes: &[u8]) -> Option<ImageFormat> {
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(ImageFormat::Jpeg)
+        } else if bytes.starts_with(b"\x89PNG\r\n\x1A\n") {
+            Some(ImageFormat::Png)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(ImageFormat::Gif)
+        } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+            Some(ImageFormat::WebP)
+        } else if is_svg(bytes) {
+            Some(ImageFormat::Svg)
+        } else {
+            None
+        }
+    }
+
+    /// Format of a content type, e.g. `image/jpeg`, also the unofficial `image/jpg` and alike
+    pub fn from_content_type(content_type: &str) -> Option<ImageFormat> {
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        match essence.to_ascii_lowercase().as_str() {
+            "image/jpeg" | "image/jpg" | "image/pjpeg" => Some(ImageFormat::Jpeg),
+            "image/png" | "image/x-png" => Some(ImageFormat::Png),
+            "image/gif" => Some(ImageFormat::Gif),
+            "image/webp" => Some(ImageFormat::WebP),
+            "image/svg+xml" | "image/svg" => Some(ImageFormat::Svg),
+            _ => None,
+        }
+    }
+
+    /// Standard content type of the format
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::Gif => "image/gif",
+            ImageFormat::WebP => "image/webp",
+            ImageFormat::Svg => "image/svg+xml",
+        }
+    }
+}
+
+/// An SVG document starts with markup and has the `svg` element near the start
+fn is_svg(bytes: &[u8]) -> bool {
+    let text = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let text = text.trim_ascii_start();
+    text.starts_with(b"<")
+        && text[..text.len().min(SVG_SNIFF_LENGTH)]
+            .windows(4)
+            .any(|window| window == b"<svg")
+}
+
+/// How far the `svg` element is looked for, after the declaration, comments, and the doctype
+const SVG_SNIFF_LENGTH: usize = 1024;
+
+/// A binary whose content type tells another format than its content
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContentTypeMismatch {
+    /// The `content-type` of the binary
+    pub declared: String,
+    /// The format of the content
+    pub actual: ImageFormat,
+}
+
+impl Binary {
+    /// Decodes the base64 content, whitespaces and a wrong padding are tolerated
+    pub fn decode(&self) -> Result<Vec<u8>, Error> {
+        if self.content.is_ascii() {
+            crate::base64::decode(self.content.as_bytes())
+        } else {
+            // e.g. non-breaking spaces
+            let content: String = self.content.split_whitespace().collect();
+            crate::base64::decode(content.as_bytes())
+        }
+    }
+
+    /// Recognizes the format of the content, only its start is decoded
+    pub fn sniff(&self) -> Option<ImageFormat> {
+        // base64 of enough bytes to find the `svg` element
+        let length = SVG_SNIFF_LENGTH.div_ceil(3) * 4;
+        let start: String = self
+            .content
+            .split_whitespace()
+            .flat_map(str::chars)
+            .take(length)
+            .collect();
+        ImageFormat::sniff(&crate::base64::decode(start.as_bytes()).ok()?)
+    }
+
+    /// Compares the content type with the format of the content. Nothing is reported
+    /// if the content is not a known image format
+    pub fn content_type_mismatch(&self) -> Option<ContentTypeMismatch> {
+        let actual = self.sniff()?;
+        if ImageFormat::from_content_type(&self.content_type) == Some(actual) {
+            return None;
+        }
+        Some(ContentTypeMismatch {
+            declared: self.content_type.clone(),
+            actual,
+        })
+    }
+}
@@ -0,0 +1,711 @@
+//! Computer readable dates of the `value` attribute, and periods guessed from the displayed text.
+//!
+//! The schema expects xs:date, but real books have years, months, and timezones there,
+//! so a date is parsed as precisely as the value allows, and the value itself is kept as is.
+//! The displayed text is free-form, e.g. `1948-53`, `XIX век`, `весна 1921`, or `c. 1850`,
+//! so it is interpreted on a best-effort basis.
+
+use std::fmt::{Display, Formatter};
+
+use chrono::{Datelike, Duration, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::Date;
+
+/// The `value` attribute of a [`Date`](crate::Date), e.g. `1953-01-01`, `1953-05`, `1953`,
+/// or any of them with a timezone, e.g. `1953-01-01+03:00`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub struct IsoDate {
+    raw: String,
+    date: Option<PartialDate>,
+    timezone: Option<FixedOffset>,
+}
+
+/// A date that may be known up to a year or a month only
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PartialDate {
+    Year(i32),
+    YearMonth { year: i32, month: u32 },
+    Full(NaiveDate),
+}
+
+impl PartialDate {
+    pub fn year(&self) -> i32 {
+        match *self {
+            PartialDate::Year(year) | PartialDate::YearMonth { year, .. } => year,
+            PartialDate::Full(date) => date.year(),
+        }
+    }
+
+    pub fn month(&self) -> Option<u32> {
+        match *self {
+            PartialDate::Year(_) => None,
+            PartialDate::YearMonth { month, .. } => Some(month),
+            PartialDate::Full(date) => Some(date.month()),
+        }
+    }
+
+    /// The first day of the period, e.g. to sort or to compare dates of different precision
+    pub fn first_day(&self) -> NaiveDate {
+        match *self {
+            PartialDate::Year(year) => NaiveDate::from_ymd_opt(year, 1, 1),
+            PartialDate::YearMonth { year, month } => NaiveDate::from_ymd_opt(year, month, 1),
+            PartialDate::Full(date) => Some(date),
+        }
+        .unwrap_or(NaiveDate::MIN)
+    }
+
+    /// The last day of the period
+    pub fn last_day(&self) -> NaiveDate {
+        match *self {
+            PartialDate::Year(year) => NaiveDate::from_ymd_opt(year, 12, 31),
+            PartialDate::YearMonth { year, month } => last_day_of_month(year, month),
+            PartialDate::Full(date) => Some(date),
+        }
+        .unwrap_or(NaiveDate::MAX)
+    }
+}
+
+impl Display for PartialDate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartialDate::Year(year) => write!(f, "{year:04}"),
+            PartialDate::YearMonth { year, month } => write!(f, "{year:04}-{month:02}"),
+            PartialDate::Full(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+        }
+    }
+}
+
+impl IsoDate {
+    /// A value of a date with an optional timezone, e.g. `1953-05` or `1953-05-01+03:00`
+    pub fn new(date: PartialDate, timezone: Option<FixedOffset>) -> IsoDate {
+        let raw = match timezone {
+            Some(timezone) => format!("{date}{timezone}"),
+            None => date.to_string(),
+        };
+        IsoDate {
+            raw,
+            date: Some(date),
+            timezone,
+        }
+    }
+
+    /// Parses a value, an invalid one is kept without a date
+    pub fn parse(raw: &str) -> IsoDate {
+        let value = raw.trim();
+        let (value, timezone) = split_timezone(value);
+        // xs:dateTime is sometimes used instead, the time is not kept
+        let value = value.split_once('T').map_or(value, |(date, _)| date);
+        IsoDate {
+            raw: raw.to_string(),
+            date: parse_date(value),
+            timezone: if value.is_empty() { None } else { timezone },
+        }
+    }
+
+    /// The value as it is in the document, it is written back on serialization
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// `None` if the value cannot be parsed
+    pub fn date(&self) -> Option<PartialDate> {
+        self.date
+    }
+
+    /// Timezone of the value if it has one, `Z` is a zero offset
+    pub fn timezone(&self) -> Option<FixedOffset> {
+        self.timezone
+    }
+
+    /// The date if it is known up to a day
+    pub fn naive_date(&self) -> Option<NaiveDate> {
+        match self.date {
+            Some(PartialDate::Full(date)) => Some(date),
+            _ => None,
+        }
+    }
+}
+
+impl From<NaiveDate> for IsoDate {
+    fn from(date: NaiveDate) -> Self {
+        IsoDate::new(PartialDate::Full(date), None)
+    }
+}
+
+impl From<String> for IsoDate {
+    fn from(raw: String) -> Self {
+        let mut date = IsoDate::parse(&raw);
+        date.raw = raw;
+        date
+    }
+}
+
+impl From<IsoDate> for String {
+    fn from(date: IsoDate) -> Self {
+        date.raw
+    }
+}
+
+/// The `year` of a [`PublishInfo`](crate::PublishInfo), e.g. `2005`, or `2005 г.` in sloppy books
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub struct Year {
+    raw: String,
+    value: Option<i32>,
+}
+
+impl Year {
+    /// Parses a value, an invalid one is kept without a value
+    pub fn parse(raw: &str) -> Year {
+        Year::from(raw.to_string())
+    }
+
+    /// The value as it is in the document, it is written back on serialization
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// `None` if the value cannot be parsed
+    pub fn value(&self) -> Option<i32> {
+        self.value
+    }
+}
+
+impl From<i32> for Year {
+    fn from(year: i32) -> Self {
+        Year {
+            raw: year.to_string(),
+            value: Some(year),
+        }
+    }
+}
+
+impl From<String> for Year {
+    fn from(raw: String) -> Self {
+        let value = raw.trim();
+        // e.g. `2005 г.` or `2005 год`
+        let value = value
+            .trim_end_matches(['.', ' '])
+            .trim_end_matches(char::is_alphabetic)
+            .trim_end();
+        let value = value
+            .parse()
+            .ok()
+            .filter(|_| value.bytes().all(|b| b.is_ascii_digit()));
+        Year { raw, value }
+    }
+}
+
+impl From<Year> for String {
+    fn from(year: Year) -> Self {
+        year.raw
+    }
+}
+
+fn split_timezone(value: &str) -> (&str, Option<FixedOffset>) {
+    if let Some(value) = value.strip_suffix(['Z', 'z']) {
+        return (value, FixedOffset::east_opt(0));
+    }
+    // e.g. `+03:00`, the sign cannot be confused with a date separator because of the colon
+    if value.len() > 6 && value.is_char_boundary(value.len() - 6) {
+        let (rest, offset) = value.split_at(value.len() - 6);
+        let sign = match offset.as_bytes()[0] {
+            b'+' => 1,
+            b'-' => -1,
+            _ => return (value, None),
+        };
+        let (hours, minutes) = match offset[1..].split_once(':') {
+            Some((hours, minutes)) => (hours.parse::<i32>(), minutes.parse::<i32>()),
+            None => return (value, None),
+        };
+        if let (Ok(hours), Ok(minutes)) = (hours, minutes) {
+            let timezone = FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60));
+            if timezone.is_some() {
+                return (rest, timezone);
+            }
+        }
+    }
+    (value, None)
+}
+
+fn parse_date(value: &str) -> Option<PartialDate> {
+    let parts: Vec<_> = value.split('-').collect();
+    if parts
+        .iter()
+        .any(|part| part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return None;
+    }
+    let year = parts[0].parse().ok()?;
+    match parts[1..] {
+        [] => Some(PartialDate::Year(year)),
+        [month] => {
+            let month = month
+                .parse()
+                .ok()
+                .filter(|month| (1..=12).contains(month))?;
+            Some(PartialDate::YearMonth { year, month })
+        }
+        [month, day] => {
+            let date = NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)?;
+            Some(PartialDate::Full(date))
+        }
+        _ => None,
+    }
+}
+
+fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    match month {
+        12 => NaiveDate::from_ymd_opt(year, 12, 31),
+        _ => NaiveDate::from_ymd_opt(year, month + 1, 1)?.pred_opt(),
+    }
+}
+
+/// A period that a date refers to, both ends are included
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub confidence: Confidence,
+}
+
+/// How likely the date is meant to be in a [`DateRange`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Confidence {
+    /// The text mentions years among something that is not understood, e.g. `Москва, 1953`
+    Low,
+    /// The text is approximate, e.g. `c. 1850`, `начало XX века`, or `winter 1921`
+    Medium,
+    /// The text names the period, e.g. `1948-53`, `XIX век`, or `весна 1921`
+    High,
+}
+
+impl DateRange {
+    /// Interprets a free-form date in Russian or English, e.g. the displayed text of a [`Date`]
+    pub fn interpret(text: &str) -> Option<DateRange> {
+        let (parts, approximate) = tokenize(text);
+        match period(&parts).or_else(|| between(&parts)) {
+            Some(range) if approximate => Some(range.at_most(Confidence::Medium)),
+            Some(range) => Some(range),
+            None => mentioned_years(&parts),
+        }
+    }
+
+    /// Whether a day is in the period
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    fn new(start: NaiveDate, end: NaiveDate, confidence: Confidence) -> DateRange {
+        DateRange {
+            start,
+            end,
+            confidence,
+        }
+    }
+
+    fn at_most(self, confidence: Confidence) -> DateRange {
+        DateRange {
+            confidence: self.confidence.min(confidence),
+            ..self
+        }
+    }
+
+    fn portion(self, portion: Portion) -> DateRange {
+        let days = (self.end - self.start).num_days() + 1;
+        let (third, half) = (Duration::days(days / 3), Duration::days(days / 2));
+        let day = Duration::days(1);
+        let (start, end) = match portion {
+            Portion::Beginning => (self.start, self.start + third - day),
+            Portion::Middle => (self.start + third, self.end - third),
+            Portion::End => (self.end - third + day, self.end),
+            Portion::FirstHalf => (self.start, self.start + half - day),
+            Portion::SecondHalf => (self.start + half, self.end),
+        };
+        // a portion of a range of a day or two is the range itself
+        let start = start.clamp(self.start, self.end);
+        let end = end.clamp(start, self.end);
+        DateRange::new(start, end, Confidence::Medium)
+    }
+}
+
+impl From<PartialDate> for DateRange {
+    fn from(date: PartialDate) -> Self {
+        DateRange::new(date.first_day(), date.last_day(), Confidence::High)
+    }
+}
+
+impl Date {
+    /// The period of the date, from `@value` if it can be parsed, and from the displayed text otherwise
+    pub fn range(&self) -> Option<DateRange> {
+        let exact = self.iso_date.as_ref().and_then(|date| date.date);
+        exact.map(DateRange::from).or_else(|| {
+            let text = self.display_date.as_deref()?;
+            DateRange::interpret(text)
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Part {
+    Number(u32, usize),
+    Word(String),
+    Dash,
+    Dot,
+    Month(u32),
+    Season(Season),
+    Portion(Portion),
+    /// e.g. `век` or `century`
+    Century,
+    /// e.g. `s` in `1920s` or `е` in `1920-е`
+    Decade,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Portion {
+    Beginning,
+    Middle,
+    End,
+    FirstHalf,
+    SecondHalf,
+}
+
+const MONTHS: [&[&str]; 12] = [
+    &["january", "jan", "январь", "января", "январе", "янв"],
+    &[
+        "february",
+        "feb",
+        "февраль",
+        "февраля",
+        "феврале",
+        "фев",
+        "февр",
+    ],
+    &["march", "mar", "март", "марта", "марте", "мар"],
+    &["april", "apr", "апрель", "апреля", "апреле", "апр"],
+    &["may", "май", "мая", "мае"],
+    &["june", "jun", "июнь", "июня", "июне", "июн"],
+    &["july", "jul", "июль", "июля", "июле", "июл"],
+    &["august", "aug", "август", "августа", "августе", "авг"],
+    &[
+        "september",
+        "sep",
+        "sept",
+        "сентябрь",
+        "сентября",
+        "сентябре",
+        "сен",
+        "сент",
+    ],
+    &["october", "oct", "октябрь", "октября", "октябре", "окт"],
+    &[
+        "november",
+        "nov",
+        "ноябрь",
+        "ноября",
+        "ноябре",
+        "ноя",
+        "нояб",
+    ],
+    &["december", "dec", "декабрь", "декабря", "декабре", "дек"],
+];
+
+const APPROXIMATE: &[&str] = &[
+    "c",
+    "ca",
+    "circa",
+    "about",
+    "around",
+    "approx",
+    "approximately",
+    "ок",
+    "около",
+    "примерно",
+    "приблизительно",
+];
+
+/// Words that are dropped, e.g. `г.` in `1953 г.` or `с` in `с 1948 по 1953`
+const FILLERS: &[&str] = &[
+    "г",
+    "гг",
+    "год",
+    "года",
+    "году",
+    "годы",
+    "годов",
+    "годах",
+    "годам",
+    "year",
+    "years",
+    "yr",
+    "the",
+    "of",
+    "in",
+    "from",
+    "between",
+    "since",
+    "в",
+    "с",
+    "со",
+    "от",
+    "между",
+];
+
+const RANGE_WORDS: &[&str] = &["to", "till", "until", "and", "по", "до", "и"];
+
+/// Suffixes of numbers, e.g. `1920s`, `1920-х`, or `19th`, that are joined with the number
+const DECADE_SUFFIXES: &[&str] = &["s", "е", "х", "ые", "ых", "ие", "их"];
+const ORDINAL_SUFFIXES: &[&str] = &["st", "nd", "rd", "th", "й", "ый", "ой", "ий", "го", "ого"];
+
+fn tokenize(text: &str) -> (Vec<Part>, bool) {
+    let text = text.to_lowercase();
+    let mut parts = Vec::new();
+    let mut approximate = false;
+    let mut rest = text.as_str();
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_ascii_digit() {
+            rest.find(|c: char| !c.is_ascii_digit())
+        } else if c.is_alphabetic() {
+            rest.find(|c: char| !c.is_alphabetic())
+        } else {
+            Some(c.len_utf8())
+        };
+        let (token, tail) = rest.split_at(len.unwrap_or(rest.len()));
+        rest = tail;
+        if c.is_ascii_digit() {
+            match token.parse() {
+                Ok(number) => parts.push(Part::Number(number, token.len())),
+                Err(_) => parts.push(Part::Word(token.to_string())),
+            }
+        } else if c.is_alphabetic() {
+            if APPROXIMATE.contains(&token) {
+                approximate = true;
+            } else if let Some(part) = classify(token, &mut parts) {
+                parts.push(part);
+            }
+        } else {
+            match c {
+                '-' | '–' | '—' | '‒' | '−' => parts.push(Part::Dash),
+                '.' => parts.push(Part::Dot),
+                '~' | '≈' | '?' => approximate = true,
+                _ => {}
+            }
+        }
+    }
+    // dots are kept only in numeric dates, e.g. `01.05.1953`
+    let parts = parts
+        .iter()
+        .enumerate()
+        .filter(|&(i, part)| {
+            *part != Part::Dot
+                || matches!(
+                    (i.checked_sub(1).map(|i| &parts[i]), parts.get(i + 1)),
+                    (Some(Part::Number(..)), Some(Part::Number(..)))
+                )
+        })
+        .map(|(_, part)| part.clone())
+        .collect();
+    (parts, approximate)
+}
+
+/// Classifies a word, it may be merged with the previous parts or dropped
+fn classify(word: &str, parts: &mut Vec<Part>) -> Option<Part> {
+    let after_number = match parts.as_slice() {
+        [.., Part::Number(..)] => true,
+        [.., Part::Number(..), Part::Dash] => {
+            let is_suffix = DECADE_SUFFIXES.contains(&word) || ORDINAL_SUFFIXES.contains(&word);
+            if is_suffix {
+                parts.pop();
+            }
+            is_suffix
+        }
+        _ => false,
+    };
+    if after_number && DECADE_SUFFIXES.contains(&word) {
+        return Some(Part::Decade);
+    }
+    if after_number && ORDINAL_SUFFIXES.contains(&word) {
+        return None;
+    }
+    if let Some(month) = MONTHS.iter().position(|forms| forms.contains(&word)) {
+        return Some(Part::Month(month as u32 + 1));
+    }
+    let after_ordinal =
+        after_number || matches!(parts.last(), Some(Part::Word(w)) if roman(w).is_some());
+    let part = match word {
+        "век" | "века" | "веке" | "веку" | "вв" | "century" | "centuries" => {
+            Part::Century
+        }
+        "в" if after_ordinal => Part::Century,
+        "весна" | "весной" | "весне" | "весны" | "spring" => {
+            Part::Season(Season::Spring)
+        }
+        "лето" | "летом" | "лета" | "summer" => Part::Season(Season::Summer),
+        "осень" | "осенью" | "осени" | "autumn" | "fall" => {
+            Part::Season(Season::Autumn)
+        }
+        "зима" | "зимой" | "зимы" | "winter" => Part::Season(Season::Winter),
+        "начало" | "начала" | "начале" | "early" | "beginning" => {
+            Part::Portion(Portion::Beginning)
+        }
+        "середина" | "середины" | "середине" | "mid" | "middle" => {
+            Part::Portion(Portion::Middle)
+        }
+        "конец" | "конца" | "конце" | "late" | "end" => Part::Portion(Portion::End),
+        "половина" | "половины" | "половине" | "half" => {
+            let portion = match parts.last() {
+                Some(Part::Word(w))
+                    if ["первая", "первой", "первую", "first"].contains(&w.as_str()) =>
+                {
+                    Portion::FirstHalf
+                }
+                Some(Part::Word(w))
+                    if ["вторая", "второй", "вторую", "second"].contains(&w.as_str()) =>
+                {
+                    Portion::SecondHalf
+                }
+                _ => return Some(Part::Word(word.to_string())),
+            };
+            parts.pop();
+            Part::Portion(portion)
+        }
+        _ if RANGE_WORDS.contains(&word) => Part::Dash,
+        _ if FILLERS.contains(&word) => return None,
+        _ => Part::Word(word.to_string()),
+    };
+    Some(part)
+}
+
+/// A single period, e.g. a day, a season, or a century
+fn period(parts: &[Part]) -> Option<DateRange> {
+    use Part::*;
+    let year = |year: &u32| i32::try_from(*year).ok();
+    match parts {
+        [Portion(portion), Dash, rest @ ..] | [Portion(portion), rest @ ..] => {
+            period(rest).map(|range| range.portion(*portion))
+        }
+        [Number(y, 3 | 4)] => Some(PartialDate::Year(year(y)?).into()),
+        [Number(y, 3 | 4), Dash, Number(m, 1 | 2)] | [Number(m, 1 | 2), Dot, Number(y, 4)] => {
+            month(year(y)?, *m)
+        }
+        [Month(m), Number(y, 3 | 4)] => month(year(y)?, *m),
+        [Number(y, 3 | 4), Dash, Number(m, 1 | 2), Dash, Number(d, 1 | 2)]
+        | [Number(d, 1 | 2), Dot, Number(m, 1 | 2), Dot, Number(y, 4)]
+        | [Number(d, 1 | 2), Month(m), Number(y, 3 | 4)]
+        | [Month(m), Number(d, 1 | 2), Number(y, 3 | 4)] => {
+            let date = NaiveDate::from_ymd_opt(year(y)?, *m, *d)?;
+            Some(PartialDate::Full(date).into())
+        }
+        [Season(season), Number(y, 3 | 4)] => self::season(*season, year(y)?),
+        [Number(y, 4), Decade] if y % 10 == 0 => {
+            let start = NaiveDate::from_ymd_opt(year(y)?, 1, 1)?;
+            let end = NaiveDate::from_ymd_opt(year(y)? + 9, 12, 31)?;
+            Some(DateRange::new(start, end, Confidence::High))
+        }
+        [Number(n, 1 | 2), Century] => century(*n),
+        [Word(w), Century] => century(roman(w)?),
+        _ => None,
+    }
+}
+
+/// Two periods, e.g. `1948-1953`, `1948-53`, or `XIX-XX вв.`
+fn between(parts: &[Part]) -> Option<DateRange> {
+    parts.iter().enumerate().find_map(|(i, part)| {
+        if *part != Part::Dash {
+            return None;
+        }
+        let (left, right) = (&parts[..i], &parts[i + 1..]);
+        let (start, end) = match (left, right) {
+            ([Part::Number(start, 4)], [Part::Number(end, 2)]) => {
+                let end = start / 100 * 100 + end;
+                (period(left)?, period(&[Part::Number(end, 4)])?)
+            }
+            ([single], [.., Part::Century]) => {
+                (period(&[single.clone(), Part::Century])?, period(right)?)
+            }
+            _ => (period(left)?, period(right)?),
+        };
+        let confidence = start.confidence.min(end.confidence);
+        (start.start <= end.end).then(|| DateRange::new(start.start, end.end, confidence))
+    })
+}
+
+/// Years mentioned anywhere in the text, when nothing else is understood
+fn mentioned_years(parts: &[Part]) -> Option<DateRange> {
+    let years = parts.iter().filter_map(|part| match part {
+        Part::Number(year, 4) => i32::try_from(*year).ok(),
+        _ => None,
+    });
+    let (first, last) = years.fold(None, |range, year| match range {
+        None => Some((year, year)),
+        Some((first, last)) => Some((year.min(first), year.max(last))),
+    })?;
+    let start = PartialDate::Year(first).first_day();
+    let end = PartialDate::Year(last).last_day();
+    Some(DateRange::new(start, end, Confidence::Low))
+}
+
+fn month(year: i32, month: u32) -> Option<DateRange> {
+    (1..=12)
+        .contains(&month)
+        .then(|| PartialDate::YearMonth { year, month }.into())
+}
+
+fn season(season: Season, year: i32) -> Option<DateRange> {
+    let (start, end, confidence) = match season {
+        Season::Spring => ((year, 3), (year, 5), Confidence::High),
+        Season::Summer => ((year, 6), (year, 8), Confidence::High),
+        Season::Autumn => ((year, 9), (year, 11), Confidence::High),
+        // it is not clear whether the winter is at the beginning or at the end of the year
+        Season::Winter => ((year - 1, 12), (year, 2), Confidence::Medium),
+    };
+    let start = NaiveDate::from_ymd_opt(start.0, start.1, 1)?;
+    let end = last_day_of_month(end.0, end.1)?;
+    Some(DateRange::new(start, end, confidence))
+}
+
+/// e.g. the 19th century is from 1801 to 1900
+fn century(number: u32) -> Option<DateRange> {
+    let number = i32::try_from(number)
+        .ok()
+        .filter(|n| (1..=30).contains(n))?;
+    let start = NaiveDate::from_ymd_opt((number - 1) * 100 + 1, 1, 1)?;
+    let end = NaiveDate::from_ymd_opt(number * 100, 12, 31)?;
+    Some(DateRange::new(start, end, Confidence::High))
+}
+
+/// Parses a lowercase roman numeral, Cyrillic letters that look the same are accepted,
+/// e.g. in `ХIХ`
+pub(crate) fn roman(word: &str) -> Option<u32> {
+    let mut total = 0;
+    let mut largest = 0;
+    for c in word.chars().rev() {
+        let value = match c {
+            'i' | 'і' => 1,
+            'v' => 5,
+            'x' | 'х' => 10,
+            'l' => 50,
+            'c' => 100,
+            'd' => 500,
+            'm' => 1000,
+            _ => return None,
+        };
+        if value < largest {
+            total = u32::checked_sub(total, value)?;
+        } else {
+            total += value;
+            largest = value;
+        }
+    }
+    (total > 0).then_some(total)
+}
@@ -0,0 +1,295 @@
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+#[cfg(feature = "encoding")]
+use crate::encoding::DetectedEncoding;
+use crate::images::{link_id, loose_id};
+use crate::scan::{Assembly, Node, Scanner};
+use crate::{Binary, Cover, Description, Error, FictionBook, Image, LazyBinary, Repair};
+
+/// Deserializes a book from a string
+pub fn from_str(s: &str) -> Result<FictionBook, Error> {
+    crate::repair::parse(s)
+}
+
+/// Deserializes a book from bytes. The content is expected to be UTF-8 unless
+/// the `encoding` feature is enabled, then the encoding is detected
+pub fn from_slice(bytes: &[u8]) -> Result<FictionBook, Error> {
+    from_str(&decode(bytes)?)
+}
+
+/// Deserializes a book from a buffered reader. The content is expected to be UTF-8 unless
+/// the `encoding` feature is enabled, then the encoding is detected
+pub fn from_reader<R: BufRead>(mut reader: R) -> Result<FictionBook, Error> {
+    // the text is kept to locate an error
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    from_slice(&bytes)
+}
+
+/// Deserializes a book from a file, see [`from_reader`]
+pub fn from_path<P: AsRef<Path>>(path: P) -> Result<FictionBook, Error> {
+    let file = File::open(path)?;
+    from_reader(BufReader::new(file))
+}
+
+/// Deserializes a book from a string, and reports every fix applied to the parts
+/// that don't conform to the FB2 schema, in the document order
+pub fn from_str_with_repairs(s: &str) -> Result<(FictionBook, Vec<Repair>), Error> {
+    crate::repair::collect(s)
+}
+
+/// Deserializes a book from a reader, see [`from_str_with_repairs`] and [`from_slice`].
+/// Offsets of the repairs refer to the source in UTF-8, and to the decoded text otherwise
+pub fn from_reader_with_repairs<R: BufRead>(
+    mut reader: R,
+) -> Result<(FictionBook, Vec<Repair>), Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    from_str_with_repairs(&decode(&bytes)?)
+}
+
+/// Deserializes a book from a string, and keeps the comments and processing instructions
+/// in the [`Extras::misc`](crate::Extras::misc) of the nearest root, description, title info,
+/// section, or paragraph, so [`to_writer`](crate::to_writer) writes them back
+pub fn from_str_with_comments(s: &str) -> Result<FictionBook, Error> {
+    crate::repair::parse_with_misc(s)
+}
+
+/// Deserializes a book from a reader, see [`from_str_with_comments`] and [`from_slice`]
+pub fn from_reader_with_comments<R: BufRead>(mut reader: R) -> Result<FictionBook, Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    from_str_with_comments(&decode(&bytes)?)
+}
+
+/// Deserializes a book from a string, and fails with [`Error::NotConforming`] on the first node
+/// that doesn't conform to the FB2 schema instead of repairing it
+pub fn from_str_strict(s: &str) -> Result<FictionBook, Error> {
+    let (book, repairs) = from_str_with_repairs(s)?;
+    match repairs.into_iter().next() {
+        Some(repair) => Err(Error::NotConforming(repair)),
+        None => Ok(book),
+    }
+}
+
+/// Deserializes a book from a reader, see [`from_str_strict`] and [`from_reader_with_repairs`]
+pub fn from_reader_strict<R: BufRead>(reader: R) -> Result<FictionBook, Error> {
+    let (book, repairs) = from_reader_with_repairs(reader)?;
+    match repairs.into_iter().next() {
+        Some(repair) => Err(Error::NotConforming(repair)),
+        None => Ok(book),
+    }
+}
+
+/// Deserializes a book from a buffered reader, and reports the detected encoding
+#[cfg(feature = "encoding")]
+pub fn from_reader_with_encoding<R: BufRead>(
+    mut reader: R,
+) -> Result<(FictionBook, DetectedEncoding), Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let (content, encoding) = crate::encoding::decode(&bytes);
+    let book = from_str(&keep_byte_order_mark(&bytes, content))?;
+    Ok((book, encoding))
+}
+
+/// Decodes a document. Offsets in the decoded text match the source if it is UTF-8
+fn decode(bytes: &[u8]) -> Result<Cow<'_, str>, Error> {
+    #[cfg(feature = "encoding")]
+    {
+        let (content, _) = crate::encoding::decode(bytes);
+        Ok(keep_byte_order_mark(bytes, content))
+    }
+    #[cfg(not(feature = "encoding"))]
+    {
+        let content = std::str::from_utf8(bytes).map_err(|e| Error::Encoding(e.to_string()))?;
+        Ok(Cow::Borrowed(content))
+    }
+}
+
+/// Puts back the byte order mark stripped by the decoding, so offsets in the text are not shifted
+#[cfg(feature = "encoding")]
+fn keep_byte_order_mark<'a>(bytes: &[u8], content: Cow<'a, str>) -> Cow<'a, str> {
+    const BOM: &str = "\u{feff}";
+    if bytes.starts_with(BOM.as_bytes()) && !content.starts_with(BOM) {
+        Cow::Owned(format!("{BOM}{content}"))
+    } else {
+        content
+    }
+}
+
+/// Deserializes a book without loading binaries into memory, so [`FictionBook::binaries`] is empty.
+/// Instead, the location of each binary in the source is returned, and the content can be decoded
+/// on demand from a seekable reader or a memory map of the same source.
+/// Documents in encodings that are not ASCII-compatible, e.g. UTF-16, are not supported
+pub fn from_reader_lazy<R: BufRead>(reader: R) -> Result<(FictionBook, Vec<LazyBinary>), Error> {
+    let mut scanner = Scanner::new(reader)?;
+    if scanner.is_transcoded() {
+        return Err(Error::Encoding(
+            "binaries cannot be located in a document that is not ASCII-compatible".to_string(),
+        ));
+    }
+    let mut document = Assembly::default();
+    let mut binaries = Vec::new();
+    let mut root = None;
+    loop {
+        match scanner.next()? {
+            Node::Start(tag) if tag.depth == 1 => {
+                scanner.open(&tag, &mut document)?;
+                root = Some(tag);
+            }
+            Node::Start(tag) if tag.depth == 2 && tag.is("binary") => {
+                let attribute = |name: &str| {
+                    scanner.attribute(&tag, name)?.ok_or_else(|| {
+                        let location = scanner.location(&tag, Some(name));
+                        Error::structure(format!("missing field `@{name}`")).at(location)
+                    })
+                };
+                let id = attribute("id")?;
+                let content_type = attribute("content-type")?;
+                let range = scanner.content_range(&tag)?;
+                binaries.push(LazyBinary {
+                    id,
+                    content_type,
+                    range,
+                });
+            }
+            Node::Start(tag) => scanner.append(&tag, &mut document)?,
+            Node::End => {
+                if let Some(root) = root.as_ref().filter(|_| scanner.depth() == 0) {
+                    scanner.close(root, &mut document)?;
+                }
+            }
+            Node::Text(_) => {}
+            Node::Eof => break,
+        }
+    }
+    Ok((scanner.parse(&document)?, binaries))
+}
+
+/// Deserializes only the description of a book, the rest of the document is not read
+pub fn read_description<R: BufRead>(reader: R) -> Result<Description, Error> {
+    let mut scanner = Scanner::new(reader)?;
+    find_description(&mut scanner)
+}
+
+/// Deserializes the description of a book, and the binary of the first cover page image that has one.
+/// Bodies are skipped without being deserialized, but the document is read up to the cover binary
+pub fn read_description_with_cover<R: BufRead>(
+    reader: R,
+) -> Result<(Description, Option<Binary>), Error> {
+    let mut scanner = Scanner::new(reader)?;
+    let description = find_description(&mut scanner)?;
+    let hrefs = cover_hrefs(&description);
+    let cover = find_binary(&mut scanner, &hrefs)?;
+    Ok((description, cover))
+}
+
+/// Reads only the cover of a book, the same one as [`FictionBook::cover`]: the first cover page
+/// image that has a binary, or else the image of the first body. The bodies are skipped without
+/// being deserialized, but the document is read up to the cover binary
+pub fn read_cover<R: BufRead>(reader: R) -> Result<Option<Cover>, Error> {
+    let mut scanner = Scanner::new(reader)?;
+    let description = find_description(&mut scanner)?;
+    let mut hrefs = cover_hrefs(&description);
+    hrefs.extend(find_body_image(&mut scanner)?);
+    let cover = find_binary(&mut scanner, &hrefs)?;
+    cover.as_ref().map(Cover::decode).transpose()
+}
+
+fn cover_hrefs(description: &Description) -> Vec<String> {
+    let covers = description.title_info.cover_page.iter();
+    let covers = covers.flat_map(|covers| &covers.images);
+    covers.filter_map(|image| image.href.clone()).collect()
+}
+
+fn find_description<R: BufRead>(scanner: &mut Scanner<R>) -> Result<Description, Error> {
+    loop {
+        match scanner.next()? {
+            Node::Start(tag) if tag.depth == 2 && tag.is("description") => {
+                return scanner.deserialize(&tag);
+            }
+            Node::Start(tag) if tag.depth == 2 => scanner.skip(&tag)?,
+            Node::Start(_) | Node::End | Node::Text(_) => {}
+            Node::Eof => {
+                let error = Error::structure("missing field `description`");
+                return Err(error.at(scanner.root_location()));
+            }
+        }
+    }
+}
+
+/// Link of the image of the first body, the image may only follow the title and epigraphs
+fn find_body_image<R: BufRead>(scanner: &mut Scanner<R>) -> Result<Option<String>, Error> {
+    let mut title = false;
+    loop {
+        match scanner.next()? {
+            Node::Start(tag) if tag.depth == 2 && tag.is("body") => {}
+            Node::Start(tag) if tag.depth == 3 && tag.is("image") => {
+                let image: Image = scanner.deserialize(&tag)?;
+                return Ok(image.href);
+            }
+            Node::Start(tag)
+                if tag.depth == 3 && (tag.is("title") && !title || tag.is("epigraph")) =>
+            {
+                title |= tag.is("title");
+                scanner.skip(&tag)?;
+            }
+            // anything else starts the sections, a later image is wrapped into one of them
+            Node::Start(tag) if tag.depth == 3 => {
+                scanner.skip(&tag)?;
+                return Ok(None);
+            }
+            Node::Text(_) if scanner.depth() == 2 => return Ok(None),
+            Node::Start(tag) => scanner.skip(&tag)?,
+            // the first body is over
+            Node::End if scanner.depth() <= 1 => return Ok(None),
+            Node::End | Node::Text(_) => {}
+            Node::Eof => return Ok(None),
+        }
+    }
+}
+
+/// Finds the binary of the first link that has one, as sloppily as [`FictionBook::find_binary`]:
+/// the first binary with the exact id, or else the first one with a similar id
+fn find_binary<R: BufRead>(
+    scanner: &mut Scanner<R>,
+    hrefs: &[String],
+) -> Result<Option<Binary>, Error> {
+    let ids: Vec<_> = hrefs
+        .iter()
+        .map(|href| (link_id(href), loose_id(link_id(href))))
+        .collect();
+    // the binary found so far with its rank, the position of the link and whether
+    // the id is only similar
+    let mut best: Option<((usize, bool), Binary)> = None;
+    loop {
+        match scanner.next()? {
+            Node::Start(tag) if tag.depth == 2 && tag.is("binary") => {
+                let rank = scanner.attribute(&tag, "id")?.and_then(|found| {
+                    let loose = loose_id(&found);
+                    ids.iter().enumerate().find_map(|(i, (id, similar))| {
+                        let exact = found == *id;
+                        (exact || loose == *similar).then_some((i, !exact))
+                    })
+                });
+                match rank {
+                    Some(rank) if best.as_ref().is_none_or(|(best, _)| rank < *best) => {
+                        best = Some((rank, scanner.deserialize(&tag)?));
+                        if rank == (0, false) {
+                            break;
+                        }
+                    }
+                    _ => scanner.skip(&tag)?,
+                }
+            }
+            Node::Start(tag) if tag.depth == 2 => scanner.skip(&tag)?,
+            Node::Start(_) | Node::End | Node::Text(_) => {}
+            Node::Eof => break,
+        }
+    }
+    Ok(best.map(|(_, binary)| binary))
+}
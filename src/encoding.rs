@@ -0,0 +1,232 @@
+//! Detection of the document encoding.
+//!
+//! Plenty of real FB2 files, especially Russian ones, are stored in legacy encodings and have
+//! a wrong or missing `encoding` in the XML declaration. The detection trusts a byte order mark,
+//! then the declaration if the content agrees with it, and falls back to a statistical guess
+//! between windows-1251, KOI8-R, and CP866 otherwise.
+
+use std::borrow::Cow;
+
+pub use encoding_rs::Encoding;
+use encoding_rs::{IBM866, KOI8_R, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1251};
+
+/// Legacy single-byte encodings that are considered by the statistical guess
+const CYRILLIC_CANDIDATES: [&Encoding; 3] = [WINDOWS_1251, KOI8_R, IBM866];
+/// Amount of bytes used to collect the statistics, enough for any reasonable annotation
+const SAMPLE_SIZE: usize = 256 * 1024;
+/// Amount of bytes searched for the XML declaration
+const DECLARATION_SIZE: usize = 1024;
+
+/// Encoding of a document and the way it was determined
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DetectedEncoding {
+    pub encoding: &'static Encoding,
+    pub source: EncodingSource,
+}
+
+/// The way an encoding was determined
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EncodingSource {
+    /// The document starts with a byte order mark
+    ByteOrderMark,
+    /// The XML declaration specifies the encoding and the content agrees with it
+    Declaration,
+    /// The XML declaration is missing, so UTF-8 is assumed and the content agrees with it
+    Default,
+    /// The declared (or default) encoding doesn't fit the content, so it was guessed
+    Guess,
+}
+
+/// Detects the encoding of a document
+pub fn detect(bytes: &[u8]) -> DetectedEncoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return DetectedEncoding {
+            encoding,
+            source: EncodingSource::ByteOrderMark,
+        };
+    }
+    match bytes {
+        [b'<', 0, b'?', 0, ..] => {
+            return DetectedEncoding {
+                encoding: UTF_16LE,
+                source: EncodingSource::Guess,
+            }
+        }
+        [0, b'<', 0, b'?', ..] => {
+            return DetectedEncoding {
+                encoding: UTF_16BE,
+                source: EncodingSource::Guess,
+            }
+        }
+        _ => {}
+    }
+
+    let declared = declared_encoding(bytes);
+    let sample = &bytes[..bytes.len().min(SAMPLE_SIZE)];
+    let source = if declared.is_some() {
+        EncodingSource::Declaration
+    } else {
+        EncodingSource::Default
+    };
+    let histogram = histogram(sample);
+    let guessed = DetectedEncoding {
+        encoding: guess_cyrillic(&histogram),
+        source: EncodingSource::Guess,
+    };
+    match declared {
+        None => {
+            if is_utf8(sample) {
+                DetectedEncoding {
+                    encoding: UTF_8,
+                    source,
+                }
+            } else {
+                guessed
+            }
+        }
+        // the content is not UTF-16, as checked above, so a UTF-16 declaration
+        // is usually a leftover from a conversion that didn't update it
+        Some(encoding) if encoding == UTF_8 || encoding == UTF_16LE || encoding == UTF_16BE => {
+            if is_utf8(sample) {
+                DetectedEncoding {
+                    encoding: UTF_8,
+                    source: if encoding == UTF_8 {
+                        source
+                    } else {
+                        EncodingSource::Guess
+                    },
+                }
+            } else {
+                guessed
+            }
+        }
+        Some(encoding) => {
+            // a multibyte UTF-8 sequence is very unlikely in a single-byte encoded text
+            if !sample.is_ascii() && is_utf8(sample) {
+                DetectedEncoding {
+                    encoding: UTF_8,
+                    source: EncodingSource::Guess,
+                }
+            } else if CYRILLIC_CANDIDATES.contains(&encoding)
+                && score(&histogram, guessed.encoding) > score(&histogram, encoding)
+            {
+                guessed
+            } else {
+                DetectedEncoding { encoding, source }
+            }
+        }
+    }
+}
+
+/// Decodes a document to a string, see [`detect`] for the details
+pub fn decode(bytes: &[u8]) -> (Cow<'_, str>, DetectedEncoding) {
+    let detected = detect(bytes);
+    let bytes = match Encoding::for_bom(bytes) {
+        Some((encoding, bom_length)) if encoding == detected.encoding => &bytes[bom_length..],
+        _ => bytes,
+    };
+    let (content, _) = detected.encoding.decode_without_bom_handling(bytes);
+    (content, detected)
+}
+
+fn declared_encoding(bytes: &[u8]) -> Option<&'static Encoding> {
+    let head = &bytes[..bytes.len().min(DECLARATION_SIZE)];
+    if !head.starts_with(b"<?xml") {
+        return None;
+    }
+    let end = head.windows(2).position(|w| w == b"?>")?;
+    let declaration = &head[..end];
+    let start = declaration.windows(8).position(|w| w == b"encoding")? + b"encoding".len();
+    let rest = declaration[start..].trim_ascii_start().strip_prefix(b"=")?;
+    let rest = rest.trim_ascii_start();
+    let (&quote, rest) = rest.split_first()?;
+    if quote != b'"' && quote != b'\'' {
+        return None;
+    }
+    let label_end = rest.iter().position(|&b| b == quote)?;
+    Encoding::for_label(&rest[..label_end])
+}
+
+fn is_utf8(bytes: &[u8]) -> bool {
+    match std::str::from_utf8(bytes) {
+        Ok(_) => true,
+        // the sample may cut a multibyte sequence in the middle
+        Err(e) => e.error_len().is_none() && bytes.len() - e.valid_up_to() < 4,
+    }
+}
+
+fn guess_cyrillic(histogram: &[u32; 128]) -> &'static Encoding {
+    CYRILLIC_CANDIDATES
+        .into_iter()
+        .max_by_key(|encoding| score(histogram, encoding))
+        .unwrap_or(WINDOWS_1251)
+}
+
+/// Counts non-ASCII bytes, the candidates differ only in them
+fn histogram(sample: &[u8]) -> [u32; 128] {
+    let mut histogram = [0; 128];
+    for &b in sample {
+        if b >= 0x80 {
+            histogram[(b - 0x80) as usize] += 1;
+        }
+    }
+    histogram
+}
+
+fn score(histogram: &[u32; 128], encoding: &'static Encoding) -> i64 {
+    let mut score = 0;
+    for (i, &count) in histogram.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        let byte = [0x80 + i as u8];
+        let (decoded, _) = encoding.decode_without_bom_handling(&byte);
+        let weight = decoded.chars().next().map_or(0, letter_weight);
+        score += weight * count as i64;
+    }
+    score
+}
+
+/// Approximate frequency of Russian letters in a text, per mille.
+/// Capital letters are rare, so they have a small weight. Other characters of legacy encodings,
+/// e.g. box drawing, don't occur in books, so they have a negative weight.
+fn letter_weight(c: char) -> i64 {
+    match c {
+        'о' => 110,
+        'е' => 85,
+        'а' => 80,
+        'и' => 74,
+        'н' => 67,
+        'т' => 63,
+        'с' => 55,
+        'р' => 47,
+        'в' => 45,
+        'л' => 44,
+        'к' => 35,
+        'м' => 32,
+        'д' => 30,
+        'п' => 28,
+        'у' => 26,
+        'я' => 20,
+        'ы' => 19,
+        'ь' => 17,
+        'г' => 17,
+        'з' => 16,
+        'б' => 16,
+        'ч' => 14,
+        'й' => 12,
+        'х' => 10,
+        'ж' => 9,
+        'ш' => 7,
+        'ю' => 6,
+        'ц' => 5,
+        'щ' => 4,
+        'э' => 3,
+        'ф' => 2,
+        'ъ' | 'ё' => 1,
+        'А'..='Я' | 'Ё' => 3,
+        // typographic punctuation that is common in books
+        '«' | '»' | '–' | '—' | '…' | '„' | '“' | '”' | '\u{a0}' | '№' => 5,
+        _ => -20,
+    }
+}
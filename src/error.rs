@@ -0,0 +1,198 @@
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use quick_xml::DeError;
+
+use crate::{Location, Repair, RepairAction};
+
+/// An error that can occur while reading an FB2 document
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The document is not a well-formed XML, e.g. a tag is not closed
+    Syntax(SyntaxError),
+    /// The document is a well-formed XML, but it cannot be mapped to a [`FictionBook`](crate::FictionBook),
+    /// e.g. a required element is missing
+    Structure(StructureError),
+    /// The document cannot be decoded to text
+    Encoding(String),
+    /// The document cannot be read
+    Io(std::io::Error),
+    /// The content of a binary cannot be decoded, e.g. it is not a valid base64
+    Binary(String),
+    /// The document doesn't conform to the FB2 schema, returned by the strict functions instead
+    /// of repairing the document. The repair tells which node is wrong and where
+    NotConforming(Repair),
+    /// The archive is corrupted or has no FB2 entries
+    #[cfg(feature = "zip")]
+    Archive(zip::result::ZipError),
+}
+
+/// A malformed XML and the place where the parser stopped.
+/// The error of the parser is the [`source`](std::error::Error::source) of [`Error::Syntax`]
+#[derive(Debug)]
+pub struct SyntaxError {
+    error: quick_xml::Error,
+    /// The innermost element that was open when the parser stopped.
+    /// Unknown if the error cannot be traced back to the document text
+    pub location: Option<Location>,
+}
+
+/// A well-formed XML that cannot be mapped to the model, and the node that causes it
+#[derive(Debug)]
+pub struct StructureError {
+    pub message: String,
+    /// The element or the attribute that cannot be mapped, e.g. `sequence[2]/@number` for
+    /// an invalid sequence number. Unknown if the error cannot be traced back to the document text
+    pub location: Option<Location>,
+}
+
+impl SyntaxError {
+    /// What is malformed, e.g. a mismatched end tag
+    pub fn message(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Display for SyntaxError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl Error {
+    /// Location of the node that causes the error, if the error relates to the document content
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Error::Syntax(e) => e.location.as_ref(),
+            Error::Structure(e) => e.location.as_ref(),
+            Error::NotConforming(repair) => Some(&repair.location),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn structure(message: impl Into<String>) -> Self {
+        Error::Structure(StructureError {
+            message: message.into(),
+            location: None,
+        })
+    }
+
+    pub(crate) fn syntax(error: quick_xml::Error) -> Self {
+        Error::Syntax(SyntaxError {
+            error,
+            location: None,
+        })
+    }
+
+    /// Sets the location of a syntax or a structure error unless it is already known
+    pub(crate) fn at(mut self, location: Option<Location>) -> Self {
+        if let Some(slot) = self.location_mut() {
+            if slot.is_none() {
+                *slot = location;
+            }
+        }
+        self
+    }
+
+    pub(crate) fn location_mut(&mut self) -> Option<&mut Option<Location>> {
+        match self {
+            Error::Syntax(e) => Some(&mut e.location),
+            Error::Structure(e) => Some(&mut e.location),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Syntax(e) => match &e.location {
+                Some(location) => write!(f, "malformed XML at {location}: {e}"),
+                None => write!(f, "malformed XML: {e}"),
+            },
+            Error::Structure(StructureError {
+                message,
+                location: Some(location),
+            }) => write!(f, "invalid FB2 structure at {location}: {message}"),
+            Error::Structure(StructureError { message, .. }) => {
+                write!(f, "invalid FB2 structure: {message}")
+            }
+            Error::Encoding(message) => write!(f, "cannot decode the document: {message}"),
+            Error::Io(e) => write!(f, "cannot read the document: {e}"),
+            Error::Binary(message) => write!(f, "invalid binary: {message}"),
+            Error::NotConforming(repair) => match repair.action {
+                RepairAction::InvalidValue => write!(
+                    f,
+                    "{}: invalid value of `{}`",
+                    repair.location, repair.element
+                ),
+                _ => write!(
+                    f,
+                    "{}: `{}` is not allowed in its place",
+                    repair.location, repair.element
+                ),
+            },
+            #[cfg(feature = "zip")]
+            Error::Archive(e) => write!(f, "invalid archive: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Syntax(e) => Some(&e.error),
+            Error::Structure(_) => None,
+            Error::Encoding(_) => None,
+            Error::Io(e) => Some(e),
+            Error::Binary(_) => None,
+            Error::NotConforming(_) => None,
+            #[cfg(feature = "zip")]
+            Error::Archive(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+#[cfg(feature = "zip")]
+impl From<zip::result::ZipError> for Error {
+    fn from(e: zip::result::ZipError) -> Self {
+        match e {
+            zip::result::ZipError::Io(e) => Error::Io(e),
+            e => Error::Archive(e),
+        }
+    }
+}
+
+impl From<quick_xml::Error> for Error {
+    fn from(e: quick_xml::Error) -> Self {
+        match e {
+            quick_xml::Error::Io(e) => Error::Io(
+                Arc::try_unwrap(e).unwrap_or_else(|e| std::io::Error::new(e.kind(), e.to_string())),
+            ),
+            quick_xml::Error::NonDecodable(Some(e)) => Error::Encoding(e.to_string()),
+            quick_xml::Error::NonDecodable(None) => {
+                Error::Encoding("the document is not a valid UTF-8".to_string())
+            }
+            e => Error::syntax(e),
+        }
+    }
+}
+
+impl From<DeError> for Error {
+    fn from(e: DeError) -> Self {
+        match e {
+            DeError::InvalidXml(e) => e.into(),
+            DeError::UnexpectedEof => {
+                Error::syntax(quick_xml::Error::UnexpectedEof("FictionBook".to_string()))
+            }
+            e => Error::structure(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,669 @@
+//! Preservation of the elements and attributes that the model doesn't know about.
+//!
+//! Serde can't capture the unknown nodes of an element, so they are collected by scanning the
+//! text before it is deserialized. The conversions of the owners take them by the offset where
+//! the owner ends, so the owners the deserializer skips don't shift the others. The unknown
+//! children of sections and paragraphs would fail the deserialization, so they are hidden
+//! in a copy of the text by comments of the same length, and the offsets of the other nodes
+//! stay valid. Every unknown node is reported as a repair, which fails the strict functions.
+//!
+//! Comments and processing instructions are collected the same way on demand, and are attached
+//! to the nearest owner. Serde can't write them, so they are serialized as placeholder elements
+//! while a book is written by [`crate::ser`], which turns the placeholders back.
+
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+use std::rc::Rc;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::NsReader;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+use crate::location::attribute_names;
+use crate::{repair, xlink, XLINK_NAMESPACE};
+
+/// Unknown attributes and child elements of an element, in the document order.
+/// Serialized after the known content of the element
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Extras {
+    /// Qualified names and values, e.g. `("xml:space", "preserve")`
+    pub attributes: Vec<(String, String)>,
+    pub elements: Vec<ExtraElement>,
+    /// Comments and processing instructions inside the element, but not inside a child owner.
+    /// The ones outside the root element belong to the root.
+    /// Collected only by [`crate::from_str_with_comments`] and [`crate::from_reader_with_comments`]
+    pub misc: Vec<Misc>,
+}
+
+impl Extras {
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty() && self.elements.is_empty() && self.misc.is_empty()
+    }
+}
+
+impl Serialize for Extras {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for (name, value) in &self.attributes {
+            map.serialize_entry(&format!("@{name}"), value)?;
+        }
+        for element in &self.elements {
+            map.serialize_entry(&element.name, element)?;
+        }
+        for misc in &self.misc {
+            misc.serialize_entry(&mut map)?;
+        }
+        map.end()
+    }
+}
+
+/// An element kept as is, together with its content
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtraElement {
+    /// Qualified name, e.g. `v:note`
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<ExtraNode>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExtraNode {
+    Element(ExtraElement),
+    Text(String),
+    Misc(Misc),
+}
+
+/// A comment or a processing instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Misc {
+    /// Content between `<!--` and `-->`
+    Comment(String),
+    /// Content between `<?` and `?>`, e.g. `xml-stylesheet href="style.css"`
+    ProcessingInstruction(String),
+}
+
+pub(crate) const COMMENT_PLACEHOLDER: &str = "fb2-comment";
+pub(crate) const INSTRUCTION_PLACEHOLDER: &str = "fb2-instruction";
+
+thread_local! {
+    static WRITING_MISC: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Serializes the comments and instructions as placeholders while the function runs,
+/// they are skipped otherwise
+pub(crate) fn with_misc<T>(f: impl FnOnce() -> T) -> T {
+    struct Reset(bool);
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            WRITING_MISC.with(|writing| writing.set(self.0));
+        }
+    }
+    let _reset = Reset(WRITING_MISC.with(|writing| writing.replace(true)));
+    f()
+}
+
+impl Misc {
+    fn serialize_entry<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        if !WRITING_MISC.with(Cell::get) {
+            return Ok(());
+        }
+        match self {
+            Misc::Comment(text) => map.serialize_entry(COMMENT_PLACEHOLDER, text),
+            Misc::ProcessingInstruction(text) => map.serialize_entry(INSTRUCTION_PLACEHOLDER, text),
+        }
+    }
+}
+
+impl Serialize for ExtraElement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for (name, value) in &self.attributes {
+            map.serialize_entry(&format!("@{name}"), value)?;
+        }
+        for child in &self.children {
+            match child {
+                ExtraNode::Element(element) => map.serialize_entry(&element.name, element)?,
+                ExtraNode::Text(text) => map.serialize_entry("$text", text)?,
+                ExtraNode::Misc(misc) => misc.serialize_entry(&mut map)?,
+            }
+        }
+        map.end()
+    }
+}
+
+/// Elements that keep the unknown nodes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Owner {
+    FictionBook,
+    Description,
+    TitleInfo,
+    Section,
+    Paragraph,
+}
+
+impl Owner {
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Whether an unknown child would fail the deserialization
+    fn is_strict(self) -> bool {
+        matches!(self, Owner::Section | Owner::Paragraph)
+    }
+
+    fn knows_attribute(self, name: &str, value: &str) -> bool {
+        match self {
+            // the namespaces of FB2 and of the links are written along with the book
+            Owner::FictionBook => {
+                name == "xmlns" || name.starts_with("xmlns:") && value == XLINK_NAMESPACE
+            }
+            Owner::Description | Owner::TitleInfo => false,
+            Owner::Section => matches!(local_name(name), "id" | "lang"),
+            Owner::Paragraph => matches!(local_name(name), "id" | "lang" | "style"),
+        }
+    }
+
+    fn knows_child(self, name: &str) -> bool {
+        let known: &[&str] = match self {
+            Owner::FictionBook => &["stylesheet", "description", "body", "binary"],
+            Owner::Description => &[
+                "title-info",
+                "src-title-info",
+                "document-info",
+                "publish-info",
+                "custom-info",
+                "output",
+            ],
+            Owner::TitleInfo => &[
+                "genre",
+                "author",
+                "book-title",
+                "annotation",
+                "keywords",
+                "date",
+                "coverpage",
+                "lang",
+                "src-lang",
+                "translator",
+                "sequence",
+            ],
+            Owner::Section => &[
+                "body",
+                "title",
+                "epigraph",
+                "image",
+                "annotation",
+                "section",
+                "p",
+                "poem",
+                "subtitle",
+                "cite",
+                "table",
+                "empty-line",
+                "stanza",
+                "v",
+                "text-author",
+                "strong",
+                "emphasis",
+                "style",
+                "a",
+                "strikethrough",
+                "sub",
+                "sup",
+                "code",
+            ],
+            Owner::Paragraph => &[
+                "p",
+                "v",
+                "text-author",
+                "title",
+                "body",
+                "stanza",
+                "poem",
+                "cite",
+                "table",
+                "empty-line",
+                "strong",
+                "emphasis",
+                "i",
+                "style",
+                "a",
+                "strikethrough",
+                "sub",
+                "sup",
+                "code",
+                "image",
+            ],
+        };
+        known.contains(&local_name(name))
+    }
+}
+
+/// How an element is treated while scanning
+enum Kind {
+    /// Keeps the unknown nodes
+    Owner(Owner),
+    /// May contain owners
+    Container,
+    /// Contains no owners, or its content is not deserialized
+    Opaque,
+}
+
+/// Tells how an element is deserialized from its local name and the local name of its parent
+fn classify(parent: Option<&str>, name: &str) -> Kind {
+    match (parent, name) {
+        (None, "FictionBook") => Kind::Owner(Owner::FictionBook),
+        (None | Some("FictionBook"), "description") => Kind::Owner(Owner::Description),
+        (None | Some("description"), "title-info" | "src-title-info") => {
+            Kind::Owner(Owner::TitleInfo)
+        }
+        (Some("FictionBook"), "body")
+        | (Some("description"), "document-info")
+        | (Some("document-info"), "history")
+        | (Some("title-info"), "annotation") => Kind::Container,
+        // the other children of these are deserialized into types that ignore unknown elements
+        (Some("FictionBook" | "description" | "title-info" | "document-info"), _) => Kind::Opaque,
+        (_, "section") => Kind::Owner(Owner::Section),
+        (_, "p" | "v" | "subtitle" | "text-author") => Kind::Owner(Owner::Paragraph),
+        (_, "image" | "empty-line" | "binary" | "stylesheet") => Kind::Opaque,
+        _ => Kind::Container,
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Extras of every owner in the order the owners end
+#[derive(Default)]
+pub(crate) struct Collected {
+    owners: [VecDeque<OwnerExtras>; 5],
+    /// Bytes consumed by the deserializer
+    position: Rc<Cell<u64>>,
+}
+
+/// Extras of an owner
+struct OwnerExtras {
+    /// Offset right after the end of the owner
+    end: u64,
+    extras: Extras,
+    /// Offsets of the unknown attributes and elements
+    unknown: Vec<u64>,
+}
+
+impl Collected {
+    /// Takes the extras of the owner converted when the current amount of bytes is consumed.
+    /// The deserializer reads one event ahead, so it's the last owner that ends before,
+    /// or the one that ends right at the end of the text. The owners before it were skipped
+    fn take(&mut self, owner: Owner) -> Option<OwnerExtras> {
+        let position = self.position.get();
+        let owners = &mut self.owners[owner.index()];
+        let before = owners.partition_point(|owner| owner.end < position);
+        let index = match before {
+            0 => owners.partition_point(|owner| owner.end <= position),
+            before => before,
+        };
+        owners.drain(..index.checked_sub(1)?);
+        owners.pop_front()
+    }
+}
+
+/// The text to deserialize and what was collected from it
+pub(crate) struct Scanned<'a> {
+    pub(crate) text: Cow<'a, str>,
+    pub(crate) collected: Collected,
+    /// Local names of the hidden elements by their offsets
+    pub(crate) hidden: HashMap<u64, String>,
+}
+
+thread_local! {
+    static COLLECTED: RefCell<Option<Collected>> = const { RefCell::new(None) };
+}
+
+/// Takes the extras of the owner being converted, they are empty unless the text was scanned
+pub(crate) fn take(owner: Owner) -> Extras {
+    let taken = COLLECTED.with(|collected| {
+        collected
+            .borrow_mut()
+            .as_mut()
+            .and_then(|collected| collected.take(owner))
+    });
+    let Some(taken) = taken else {
+        return Extras::default();
+    };
+    for offset in taken.unknown {
+        repair::kept(offset);
+    }
+    taken.extras
+}
+
+/// Makes the extras available to the conversions of the current thread, and clears them when
+/// dropped, also when the deserializer panics. Sessions do not nest: the conversions could not
+/// tell which document an owner belongs to
+pub(crate) struct Session(());
+
+impl Session {
+    /// Starts a session for a deserializer that counts the consumed bytes in `position`
+    pub(crate) fn start(mut collected: Collected, position: Rc<Cell<u64>>) -> Self {
+        collected.position = position;
+        COLLECTED.with(|c| {
+            let mut current = c.borrow_mut();
+            assert!(current.is_none(), "extras of another document are in use");
+            *current = Some(collected);
+        });
+        Session(())
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        COLLECTED.with(|c| c.take());
+    }
+}
+
+enum Frame {
+    Element {
+        name: String,
+        kind: Kind,
+    },
+    Owner {
+        owner: Owner,
+        extras: Extras,
+        unknown: Vec<u64>,
+    },
+    /// An unknown element being captured
+    Extra {
+        element: ExtraElement,
+        start: usize,
+        /// Whether it would fail the deserialization
+        hidden: bool,
+    },
+}
+
+/// Collects the unknown nodes of the owners, and the comments and instructions if `misc` is set,
+/// and hides the nodes that would fail the deserialization.
+/// A malformed text is left as is to let the deserializer report the error
+pub(crate) fn collect(source: &str, misc: bool) -> Scanned<'_> {
+    scan(source, misc).unwrap_or_else(|_| Scanned {
+        text: Cow::Borrowed(source),
+        collected: Collected::default(),
+        hidden: HashMap::new(),
+    })
+}
+
+fn scan(source: &str, keep_misc: bool) -> Result<Scanned<'_>, quick_xml::Error> {
+    let mut reader = NsReader::from_str(source);
+    let mut collected = Collected::default();
+    let mut hidden: Vec<(Range<usize>, String)> = vec![];
+    let mut ignored = vec![];
+    let mut stack: Vec<Frame> = vec![];
+    let mut root = None;
+    let (mut prolog, mut epilog) = (vec![], vec![]);
+    loop {
+        let start = reader.buffer_position();
+        let event = reader.read_event()?;
+        match event {
+            Event::Start(tag) => {
+                let text = &source[start..reader.buffer_position()];
+                if !matches!(stack.last(), Some(Frame::Extra { .. })) {
+                    ignored.extend(xlink::ignored_attributes(&reader, &tag, text, start)?);
+                }
+                let frame = open(&stack, &tag, text, start)?;
+                if let (None, Frame::Owner { owner, .. }) = (stack.first(), &frame) {
+                    root = Some(*owner);
+                }
+                stack.push(frame);
+            }
+            Event::Empty(tag) => {
+                let text = &source[start..reader.buffer_position()];
+                if !matches!(stack.last(), Some(Frame::Extra { .. })) {
+                    ignored.extend(xlink::ignored_attributes(&reader, &tag, text, start)?);
+                }
+                let frame = open(&stack, &tag, text, start)?;
+                close(
+                    &mut stack,
+                    frame,
+                    reader.buffer_position(),
+                    &mut collected,
+                    &mut hidden,
+                );
+            }
+            Event::End(_) => {
+                if let Some(frame) = stack.pop() {
+                    close(
+                        &mut stack,
+                        frame,
+                        reader.buffer_position(),
+                        &mut collected,
+                        &mut hidden,
+                    );
+                }
+            }
+            Event::Text(text) => {
+                if let Some(Frame::Extra { element, .. }) = stack.last_mut() {
+                    element
+                        .children
+                        .push(ExtraNode::Text(text.unescape()?.into_owned()));
+                }
+            }
+            Event::CData(text) => {
+                if let Some(Frame::Extra { element, .. }) = stack.last_mut() {
+                    let text = String::from_utf8_lossy(&text).into_owned();
+                    element.children.push(ExtraNode::Text(text));
+                }
+            }
+            Event::Comment(text) if keep_misc => {
+                let text = String::from_utf8_lossy(&text).into_owned();
+                let outside = if root.is_some() {
+                    &mut epilog
+                } else {
+                    &mut prolog
+                };
+                attach(&mut stack, Misc::Comment(text), outside);
+            }
+            Event::PI(text) if keep_misc => {
+                let text = String::from_utf8_lossy(&text).into_owned();
+                let outside = if root.is_some() {
+                    &mut epilog
+                } else {
+                    &mut prolog
+                };
+                attach(&mut stack, Misc::ProcessingInstruction(text), outside);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if let Some(root) = root.and_then(|owner| collected.owners[owner.index()].back_mut()) {
+        root.extras.misc.splice(0..0, prolog);
+        root.extras.misc.extend(epilog);
+    }
+    if hidden.is_empty() && ignored.is_empty() {
+        return Ok(Scanned {
+            text: Cow::Borrowed(source),
+            collected,
+            hidden: HashMap::new(),
+        });
+    }
+    let mut text = source.as_bytes().to_vec();
+    for (range, _) in &hidden {
+        hide(&mut text[range.clone()]);
+    }
+    for end in ignored {
+        xlink::ignore(&mut text, end);
+    }
+    let text = String::from_utf8(text).expect("only ASCII characters are replaced");
+    let hidden = hidden
+        .into_iter()
+        .map(|(range, name)| (range.start as u64, local_name(&name).to_string()))
+        .collect();
+    Ok(Scanned {
+        text: Cow::Owned(text),
+        collected,
+        hidden,
+    })
+}
+
+/// Attaches a comment or an instruction to the innermost captured element or owner
+fn attach(stack: &mut [Frame], misc: Misc, outside: &mut Vec<Misc>) {
+    for frame in stack.iter_mut().rev() {
+        match frame {
+            Frame::Extra { element, .. } => return element.children.push(ExtraNode::Misc(misc)),
+            Frame::Owner { extras, .. } => return extras.misc.push(misc),
+            Frame::Element { .. } => {}
+        }
+    }
+    outside.push(misc);
+}
+
+/// Opens an element with the start tag `text` at `start`
+fn open(
+    stack: &[Frame],
+    tag: &BytesStart,
+    text: &str,
+    start: usize,
+) -> Result<Frame, quick_xml::Error> {
+    let name = String::from_utf8_lossy(tag.name().as_ref()).into_owned();
+    let parent = match stack.last() {
+        Some(Frame::Extra { .. }) => {
+            return Ok(Frame::Extra {
+                element: extra_element(name, tag)?,
+                start,
+                hidden: false,
+            })
+        }
+        Some(Frame::Element {
+            kind: Kind::Opaque, ..
+        }) => {
+            return Ok(Frame::Element {
+                name,
+                kind: Kind::Opaque,
+            })
+        }
+        Some(Frame::Owner { owner, .. }) if !owner.knows_child(&name) => {
+            return Ok(Frame::Extra {
+                element: extra_element(name, tag)?,
+                start,
+                hidden: owner.is_strict(),
+            })
+        }
+        Some(Frame::Element { name, .. }) => Some(local_name(name)),
+        Some(Frame::Owner { owner, .. }) => Some(owner.element_name()),
+        None => None,
+    };
+    match classify(parent, local_name(&name)) {
+        Kind::Owner(owner) => {
+            let mut extras = Extras::default();
+            let mut unknown = vec![];
+            for (attribute, span) in tag.attributes().zip(attribute_names(text)) {
+                let attribute = attribute?;
+                let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+                let value = attribute.unescape_value()?.into_owned();
+                if owner.knows_attribute(&key, &value) {
+                    continue;
+                }
+                // the namespaces conform wherever they are declared
+                if key != "xmlns" && !key.starts_with("xmlns:") {
+                    unknown.push((start + span.start) as u64);
+                }
+                extras.attributes.push((key, value));
+            }
+            Ok(Frame::Owner {
+                owner,
+                extras,
+                unknown,
+            })
+        }
+        kind => Ok(Frame::Element { name, kind }),
+    }
+}
+
+impl Owner {
+    /// The name the children are classified against, the local name doesn't matter otherwise
+    fn element_name(self) -> &'static str {
+        match self {
+            Owner::FictionBook => "FictionBook",
+            Owner::Description => "description",
+            Owner::TitleInfo => "title-info",
+            Owner::Section => "section",
+            Owner::Paragraph => "p",
+        }
+    }
+}
+
+fn extra_element(name: String, tag: &BytesStart) -> Result<ExtraElement, quick_xml::Error> {
+    let mut attributes = vec![];
+    for attribute in tag.attributes() {
+        let attribute = attribute?;
+        let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+        attributes.push((key, attribute.unescape_value()?.into_owned()));
+    }
+    Ok(ExtraElement {
+        name,
+        attributes,
+        children: vec![],
+    })
+}
+
+fn close(
+    stack: &mut [Frame],
+    frame: Frame,
+    end: usize,
+    collected: &mut Collected,
+    hidden: &mut Vec<(Range<usize>, String)>,
+) {
+    match frame {
+        Frame::Element { .. } => {}
+        Frame::Owner {
+            owner,
+            extras,
+            unknown,
+        } => collected.owners[owner.index()].push_back(OwnerExtras {
+            end: end as u64,
+            extras,
+            unknown,
+        }),
+        Frame::Extra {
+            element,
+            start,
+            hidden: hide,
+        } => {
+            if hide {
+                hidden.push((start..end, element.name.clone()));
+            }
+            match stack.last_mut() {
+                Some(Frame::Extra {
+                    element: parent, ..
+                }) => parent.children.push(ExtraNode::Element(element)),
+                Some(Frame::Owner {
+                    extras, unknown, ..
+                }) => {
+                    unknown.push(start as u64);
+                    extras.elements.push(element);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Replaces an element by a comment or a processing instruction of the same length in bytes,
+/// or by spaces if it's too short for both. The line breaks are kept for the locations
+fn hide(element: &mut [u8]) {
+    let (open, close): (&[u8], &[u8]) = match element.len() {
+        7.. => (b"<!--", b"-->"),
+        5 | 6 => (b"<?x", b"?>"),
+        _ => (b"", b""),
+    };
+    let length = element.len();
+    for byte in element.iter_mut() {
+        if *byte != b'\n' {
+            *byte = b' ';
+        }
+    }
+    element[..open.len()].copy_from_slice(open);
+    element[length - close.len()..].copy_from_slice(close);
+}
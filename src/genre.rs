@@ -0,0 +1,467 @@
+//! Genres of the FB2 genre list, with their names and groups for a genre browser.
+
+use serde::{Deserialize, Serialize};
+
+/// Language of the genre names
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Locale {
+    English,
+    Russian,
+}
+
+macro_rules! genres {
+    ($($variant:ident => $code:literal, $group:ident, $en:literal, $ru:literal;)*) => {
+        /// Genre of a book by its code, e.g. `sf_history`.
+        /// Codes that are not in the list, e.g. site-specific ones, are kept as [`Genre::Other`]
+        #[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum Genre {
+            $($variant,)*
+            /// The genre is not specified
+            #[default]
+            Unrecognised,
+            /// A code that is not in the list
+            Other(String),
+        }
+
+        const KNOWN: &[Genre] = &[$(Genre::$variant,)*];
+
+        impl Genre {
+            /// Code of the genre as it appears in a document
+            pub fn code(&self) -> &str {
+                match self {
+                    $(Genre::$variant => $code,)*
+                    Genre::Unrecognised => "unrecognised",
+                    Genre::Other(code) => code,
+                }
+            }
+
+            /// Human-readable name of a genre from the list
+            pub fn name(&self, locale: Locale) -> Option<&'static str> {
+                match (self, locale) {
+                    $(
+                        (Genre::$variant, Locale::English) => Some($en),
+                        (Genre::$variant, Locale::Russian) => Some($ru),
+                    )*
+                    (Genre::Unrecognised | Genre::Other(_), _) => None,
+                }
+            }
+
+            /// Group of a genre from the list
+            pub fn group(&self) -> Option<GenreGroup> {
+                match self {
+                    $(Genre::$variant => Some(GenreGroup::$group),)*
+                    Genre::Unrecognised | Genre::Other(_) => None,
+                }
+            }
+        }
+
+        impl From<String> for Genre {
+            fn from(code: String) -> Self {
+                match code.as_str() {
+                    $($code => Genre::$variant,)*
+                    "" | "unrecognised" => Genre::Unrecognised,
+                    _ => Genre::Other(code),
+                }
+            }
+        }
+    };
+}
+
+impl Genre {
+    /// Every genre of the list, in the order of the codes, including the aliases
+    pub fn known() -> impl Iterator<Item = &'static Genre> {
+        KNOWN.iter()
+    }
+
+    /// The genre this one is an alias of, so a genre is counted once in search and facets.
+    /// Codes that are not in the list are looked up among the codes of older genre lists and
+    /// other FB2 tools, after the case and the separators are normalized.
+    /// The genre itself keeps the code of the document
+    pub fn canonical(&self) -> Genre {
+        let genre = match self {
+            Genre::Other(code) => {
+                let code = code.trim().to_ascii_lowercase().replace(['-', ' '], "_");
+                match LEGACY_CODES.iter().find(|(legacy, _)| *legacy == code) {
+                    Some((_, genre)) => genre.clone(),
+                    None => Genre::from(code),
+                }
+            }
+            genre => genre.clone(),
+        };
+        match genre {
+            Genre::Other(_) => self.clone(),
+            genre => alias_of(&genre).unwrap_or(genre),
+        }
+    }
+
+    /// Whether the genre is not an alias of another one, see [`Genre::canonical`]
+    pub fn is_canonical(&self) -> bool {
+        self.canonical() == *self
+    }
+}
+
+/// Genres of the list that duplicate another one
+fn alias_of(genre: &Genre) -> Option<Genre> {
+    let canonical = match genre {
+        Genre::ArchitectureBook => Genre::Architecture,
+        Genre::HorrorVampires => Genre::VampireBook,
+        Genre::LiteratureAdv => Genre::Adventure,
+        Genre::LiteratureFairy => Genre::ChildTale,
+        Genre::LiteratureHistory => Genre::ProseHistory,
+        Genre::RomanceContemporary => Genre::LoveContemporary,
+        Genre::RomanceFantasy => Genre::LoveFantasy,
+        Genre::RomanceHistorical => Genre::LoveHistory,
+        Genre::RomanceRomanticSuspense => Genre::LoveDetective,
+        Genre::RomanceSf => Genre::LoveSf,
+        Genre::SciEconomy => Genre::Economics,
+        Genre::SciReligion => Genre::ReligionRel,
+        Genre::SfCyberPunk => Genre::SfCyberpunk,
+        Genre::SfFantasyCity => Genre::CityFantasy,
+        _ => return None,
+    };
+    Some(canonical)
+}
+
+/// Codes of older genre lists and other FB2 tools
+const LEGACY_CODES: &[(&str, Genre)] = &[
+    ("fantasy", Genre::SfFantasy),
+    ("sf_irony", Genre::SfHumor),
+    ("sf_fantasy_irony", Genre::HumorFantasy),
+    ("sf_postapocalypse", Genre::SfPostapocalyptic),
+    ("romance", Genre::Love),
+    ("love_romance", Genre::Love),
+    ("love_hard", Genre::LoveErotica),
+    ("literature_classics", Genre::ProseClassic),
+    ("literature_rus_classic", Genre::ProseRusClassic),
+    ("literature_rus_classsic", Genre::ProseRusClassic),
+    ("literature_su_classics", Genre::ProseSuClassics),
+    ("prose_su_classic", Genre::ProseSuClassics),
+    ("literature_war", Genre::ProseMilitary),
+    ("literature_short", Genre::ShortStory),
+    ("nonf_military", Genre::MilitarySpecial),
+    ("nonfiction_biography", Genre::NonfBiography),
+    ("thriller_legal", Genre::Thriller),
+    ("thriller_medical", Genre::Thriller),
+    ("thriller_techno", Genre::Thriller),
+    ("home_collecting", Genre::HomeCrafts),
+    ("sci_geo", Genre::GeographyBook),
+    ("sci_zoo", Genre::SciBiology),
+    ("sci_botany", Genre::SciBiology),
+    ("sci_economics", Genre::Economics),
+    ("comp_internet", Genre::CompWww),
+];
+
+/// A group of related genres, e.g. science fiction
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GenreGroup {
+    ScienceFiction,
+    Detective,
+    Prose,
+    Romance,
+    Adventure,
+    Children,
+    Poetry,
+    Antique,
+    Science,
+    Computers,
+    Reference,
+    Nonfiction,
+    Religion,
+    Humor,
+    Home,
+    Business,
+    Art,
+    Other,
+}
+
+impl GenreGroup {
+    pub const ALL: [GenreGroup; 18] = [
+        GenreGroup::ScienceFiction,
+        GenreGroup::Detective,
+        GenreGroup::Prose,
+        GenreGroup::Romance,
+        GenreGroup::Adventure,
+        GenreGroup::Children,
+        GenreGroup::Poetry,
+        GenreGroup::Antique,
+        GenreGroup::Science,
+        GenreGroup::Computers,
+        GenreGroup::Reference,
+        GenreGroup::Nonfiction,
+        GenreGroup::Religion,
+        GenreGroup::Humor,
+        GenreGroup::Home,
+        GenreGroup::Business,
+        GenreGroup::Art,
+        GenreGroup::Other,
+    ];
+
+    /// Human-readable name of the group
+    pub fn name(self, locale: Locale) -> &'static str {
+        let (english, russian) = match self {
+            GenreGroup::ScienceFiction => ("Science Fiction & Fantasy", "Фантастика"),
+            GenreGroup::Detective => ("Detectives & Thrillers", "Детективы и триллеры"),
+            GenreGroup::Prose => ("Prose", "Проза"),
+            GenreGroup::Romance => ("Romance", "Любовные романы"),
+            GenreGroup::Adventure => ("Adventure", "Приключения"),
+            GenreGroup::Children => ("Children's", "Детское"),
+            GenreGroup::Poetry => ("Poetry & Dramaturgy", "Поэзия и драматургия"),
+            GenreGroup::Antique => ("Antique Literature", "Старинное"),
+            GenreGroup::Science => ("Science & Education", "Наука и образование"),
+            GenreGroup::Computers => ("Computers & Internet", "Компьютеры и интернет"),
+            GenreGroup::Reference => ("Reference", "Справочная литература"),
+            GenreGroup::Nonfiction => ("Nonfiction", "Документальная литература"),
+            GenreGroup::Religion => ("Religion & Spirituality", "Религия и духовность"),
+            GenreGroup::Humor => ("Humor", "Юмор"),
+            GenreGroup::Home => ("Home & Family", "Дом и семья"),
+            GenreGroup::Business => ("Business", "Экономика и бизнес"),
+            GenreGroup::Art => ("Art", "Искусство"),
+            GenreGroup::Other => ("Other", "Прочее"),
+        };
+        match locale {
+            Locale::English => english,
+            Locale::Russian => russian,
+        }
+    }
+
+    /// Genres of the list that belong to the group, without the aliases
+    pub fn genres(self) -> impl Iterator<Item = &'static Genre> {
+        Genre::known().filter(move |genre| genre.group() == Some(self) && genre.is_canonical())
+    }
+}
+
+genres! {
+    Accounting => "accounting", Business, "Accounting", "Бухучет и аудит";
+    AdvAnimal => "adv_animal", Adventure, "Nature and animals", "Природа и животные";
+    AdvGeo => "adv_geo", Adventure, "Travel and geography", "Путешествия и география";
+    AdvHistory => "adv_history", Adventure, "Historical adventure", "Исторические приключения";
+    AdvIndian => "adv_indian", Adventure, "Adventures of Indians", "Приключения про индейцев";
+    AdvMaritime => "adv_maritime", Adventure, "Maritime adventure", "Морские приключения";
+    AdvWestern => "adv_western", Adventure, "Western", "Вестерн";
+    Adventure => "adventure", Adventure, "Adventure", "Приключения";
+    Antique => "antique", Antique, "Antique literature", "Старинная литература";
+    AntiqueAnt => "antique_ant", Antique, "Ancient literature", "Античная литература";
+    AntiqueEast => "antique_east", Antique, "Ancient Eastern literature", "Древневосточная литература";
+    AntiqueEuropean => "antique_european", Antique, "European antique literature", "Европейская старинная литература";
+    AntiqueMyths => "antique_myths", Antique, "Myths, legends, epic", "Мифы. Легенды. Эпос";
+    AntiqueRussian => "antique_russian", Antique, "Old Russian literature", "Древнерусская литература";
+    AphorismQuote => "aphorism_quote", Prose, "Aphorisms and quotes", "Афоризмы и цитаты";
+    Architecture => "architecture", Art, "Architecture", "Архитектура";
+    ArchitectureBook => "architecture_book", Art, "Architecture", "Архитектура";
+    AutoRegulations => "auto_regulations", Home, "Cars and traffic rules", "Автомобили и ПДД";
+    Banking => "banking", Business, "Banking", "Банковское дело";
+    BeginningAuthors => "beginning_authors", Prose, "Beginning authors", "Начинающие авторы";
+    Business => "business", Business, "Business", "Бизнес";
+    ChildAdv => "child_adv", Children, "Adventure for children", "Приключения для детей";
+    ChildDet => "child_det", Children, "Detectives for children", "Детские детективы";
+    ChildEducation => "child_education", Children, "Educational literature for children", "Детская образовательная литература";
+    ChildProse => "child_prose", Children, "Prose for children", "Детская проза";
+    ChildSf => "child_sf", Children, "Science fiction for children", "Детская фантастика";
+    ChildTale => "child_tale", Children, "Fairy tales", "Сказки";
+    ChildVerse => "child_verse", Children, "Poetry for children", "Детские стихи";
+    Children => "children", Children, "Children's literature", "Детская литература";
+    CinemaTheatre => "cinema_theatre", Art, "Cinema and theatre", "Кино и театр";
+    CityFantasy => "city_fantasy", ScienceFiction, "Urban fantasy", "Городское фэнтези";
+    CompDb => "comp_db", Computers, "Databases", "Базы данных";
+    CompHard => "comp_hard", Computers, "Hardware", "Компьютерное железо";
+    CompOsnet => "comp_osnet", Computers, "Operating systems and networks", "ОС и сети";
+    CompProgramming => "comp_programming", Computers, "Programming", "Программирование";
+    CompSoft => "comp_soft", Computers, "Software", "Программы";
+    CompWww => "comp_www", Computers, "Internet", "Интернет";
+    Computers => "computers", Computers, "Computers", "Компьютеры";
+    Design => "design", Art, "Design", "Дизайн";
+    DetAction => "det_action", Detective, "Action", "Боевик";
+    DetClassic => "det_classic", Detective, "Classic detective", "Классический детектив";
+    DetCrime => "det_crime", Detective, "Crime detective", "Криминальный детектив";
+    DetEspionage => "det_espionage", Detective, "Espionage detective", "Шпионский детектив";
+    DetHard => "det_hard", Detective, "Hardboiled detective", "Крутой детектив";
+    DetHistory => "det_history", Detective, "Historical detective", "Исторический детектив";
+    DetIrony => "det_irony", Detective, "Ironic detective", "Иронический детектив";
+    DetManiac => "det_maniac", Detective, "Maniacs", "Про маньяков";
+    DetPolice => "det_police", Detective, "Police procedural", "Полицейский детектив";
+    DetPolitical => "det_political", Detective, "Political detective", "Политический детектив";
+    Detective => "detective", Detective, "Detective", "Детектив";
+    DragonFantasy => "dragon_fantasy", ScienceFiction, "Dragon fantasy", "Фэнтези про драконов";
+    Dramaturgy => "dramaturgy", Poetry, "Dramaturgy", "Драматургия";
+    Economics => "economics", Business, "Economics", "Экономика";
+    EconomicsRef => "economics_ref", Business, "Business reference", "Деловая литература";
+    EntertHumor => "entert_humor", Humor, "Entertaining humor", "Развлекательный юмор";
+    Essays => "essays", Prose, "Essays", "Эссе";
+    FantasyFight => "fantasy_fight", ScienceFiction, "Battle fantasy", "Боевое фэнтези";
+    ForeignAction => "foreign_action", Detective, "Foreign action", "Зарубежный боевик";
+    ForeignAdventure => "foreign_adventure", Adventure, "Foreign adventure", "Зарубежные приключения";
+    ForeignAntique => "foreign_antique", Antique, "Foreign antique literature", "Зарубежная старинная литература";
+    ForeignBusiness => "foreign_business", Business, "Foreign business literature", "Зарубежная деловая литература";
+    ForeignChildren => "foreign_children", Children, "Foreign children's literature", "Зарубежная детская литература";
+    ForeignComp => "foreign_comp", Computers, "Foreign computer literature", "Зарубежная компьютерная литература";
+    ForeignContemporary => "foreign_contemporary", Prose, "Foreign contemporary prose", "Зарубежная современная проза";
+    ForeignContemporaryLit => "foreign_contemporary_lit", Prose, "Foreign contemporary literature", "Зарубежная современная литература";
+    ForeignDesc => "foreign_desc", Reference, "Foreign reference literature", "Зарубежная справочная литература";
+    ForeignDetective => "foreign_detective", Detective, "Foreign detective", "Зарубежный детектив";
+    ForeignDramaturgy => "foreign_dramaturgy", Poetry, "Foreign dramaturgy", "Зарубежная драматургия";
+    ForeignEdu => "foreign_edu", Science, "Foreign educational literature", "Зарубежная образовательная литература";
+    ForeignFantasy => "foreign_fantasy", ScienceFiction, "Foreign fantasy", "Зарубежное фэнтези";
+    ForeignHome => "foreign_home", Home, "Foreign home and family literature", "Зарубежная литература о доме и семье";
+    ForeignHumor => "foreign_humor", Humor, "Foreign humor", "Зарубежный юмор";
+    ForeignLanguage => "foreign_language", Science, "Foreign languages", "Иностранные языки";
+    ForeignLove => "foreign_love", Romance, "Foreign romance", "Зарубежные любовные романы";
+    ForeignNovel => "foreign_novel", Prose, "Foreign novels", "Зарубежные романы";
+    ForeignOther => "foreign_other", Other, "Other foreign literature", "Зарубежная литература: прочее";
+    ForeignPoetry => "foreign_poetry", Poetry, "Foreign poetry", "Зарубежная поэзия";
+    ForeignProse => "foreign_prose", Prose, "Foreign classic prose", "Зарубежная классическая проза";
+    ForeignPsychology => "foreign_psychology", Science, "Foreign psychology", "Зарубежная психология";
+    ForeignPublicism => "foreign_publicism", Nonfiction, "Foreign publicism", "Зарубежная публицистика";
+    ForeignReligion => "foreign_religion", Religion, "Foreign religious literature", "Зарубежная религиозная литература";
+    ForeignSf => "foreign_sf", ScienceFiction, "Foreign science fiction", "Зарубежная фантастика";
+    GeoGuides => "geo_guides", Reference, "Travel guides", "Путеводители";
+    GeographyBook => "geography_book", Science, "Geography", "География";
+    GlobalEconomy => "global_economy", Business, "Global economy", "Мировая экономика";
+    HealthPsy => "health_psy", Home, "Mental health", "Психология здоровья";
+    HistoricalFantasy => "historical_fantasy", ScienceFiction, "Historical fantasy", "Историческое фэнтези";
+    Home => "home", Home, "Home and family", "Дом и семья";
+    HomeCooking => "home_cooking", Home, "Cooking", "Кулинария";
+    HomeCrafts => "home_crafts", Home, "Hobbies and crafts", "Хобби и ремесла";
+    HomeDiy => "home_diy", Home, "Do it yourself", "Сделай сам";
+    HomeEntertain => "home_entertain", Home, "Entertainment", "Развлечения";
+    HomeGarden => "home_garden", Home, "Garden", "Сад и огород";
+    HomeHealth => "home_health", Home, "Health", "Здоровье";
+    HomePets => "home_pets", Home, "Pets", "Домашние животные";
+    HomeSex => "home_sex", Home, "Erotica and sex", "Эротика и секс";
+    HomeSport => "home_sport", Home, "Sports", "Спорт";
+    HorrorFantasy => "horror_fantasy", ScienceFiction, "Horror fantasy", "Фэнтези ужасов";
+    HorrorVampires => "horror_vampires", ScienceFiction, "Vampires", "Вампиры";
+    Humor => "humor", Humor, "Humor", "Юмор";
+    HumorAnecdote => "humor_anecdote", Humor, "Anecdotes", "Анекдоты";
+    HumorFantasy => "humor_fantasy", ScienceFiction, "Humorous fantasy", "Юмористическое фэнтези";
+    HumorProse => "humor_prose", Humor, "Humorous prose", "Юмористическая проза";
+    HumorVerse => "humor_verse", Humor, "Humorous verse", "Юмористические стихи";
+    Industries => "industries", Business, "Industries", "Отраслевые издания";
+    JobHunting => "job_hunting", Business, "Job hunting", "Поиск работы";
+    Literature18 => "literature18", Prose, "18th century literature", "Литература XVIII века";
+    Literature19 => "literature19", Prose, "19th century literature", "Литература XIX века";
+    Literature20 => "literature20", Prose, "20th century literature", "Литература XX века";
+    LiteratureAdv => "literature_adv", Adventure, "Adventure literature", "Приключенческая литература";
+    LiteratureFairy => "literature_fairy", Children, "Fairy tales", "Сказки";
+    LiteratureHistory => "literature_history", Prose, "Historical literature", "Историческая литература";
+    Love => "love", Romance, "Romance", "Любовные романы";
+    LoveContemporary => "love_contemporary", Romance, "Contemporary romance", "Современные любовные романы";
+    LoveDetective => "love_detective", Romance, "Romantic suspense", "Остросюжетные любовные романы";
+    LoveErotica => "love_erotica", Romance, "Erotica", "Эротика";
+    LoveFantasy => "love_fantasy", Romance, "Fantasy romance", "Любовное фэнтези";
+    LoveHistory => "love_history", Romance, "Historical romance", "Исторические любовные романы";
+    LoveSf => "love_sf", Romance, "Science fiction romance", "Любовная фантастика";
+    LoveShort => "love_short", Romance, "Short romance", "Короткие любовные романы";
+    MagicianBook => "magician_book", ScienceFiction, "Magic", "Магия";
+    Management => "management", Business, "Management", "Управление и подбор персонала";
+    Marketing => "marketing", Business, "Marketing", "Маркетинг, PR, реклама";
+    MilitarySpecial => "military_special", Nonfiction, "Military literature", "Военное дело";
+    MusicDancing => "music_dancing", Art, "Music and dancing", "Музыка и танцы";
+    Narrative => "narrative", Prose, "Narrative", "Повествование";
+    Newspapers => "newspapers", Other, "Newspapers", "Газеты";
+    NonfBiography => "nonf_biography", Nonfiction, "Biographies and memoirs", "Биографии и мемуары";
+    NonfCriticism => "nonf_criticism", Nonfiction, "Criticism", "Критика";
+    NonfPublicism => "nonf_publicism", Nonfiction, "Publicism", "Публицистика";
+    Nonfiction => "nonfiction", Nonfiction, "Nonfiction", "Документальная литература";
+    NonfictionPolitics => "nonfiction_politics", Nonfiction, "Politics", "Политика";
+    NonfictionSociology => "nonfiction_sociology", Nonfiction, "Sociology", "Социология";
+    OrgBehavior => "org_behavior", Business, "Corporate culture", "Корпоративная культура";
+    PaperWork => "paper_work", Business, "Paperwork", "Делопроизводство";
+    PedagogyBook => "pedagogy_book", Science, "Pedagogy", "Педагогика";
+    Periodic => "periodic", Other, "Periodicals", "Периодические издания";
+    PersonalFinance => "personal_finance", Business, "Personal finance", "Личные финансы";
+    Poetry => "poetry", Poetry, "Poetry", "Поэзия";
+    Popadanec => "popadanec", ScienceFiction, "Time travel", "Попаданцы";
+    PopularBusiness => "popular_business", Business, "Popular business", "Карьера, кадры";
+    Prose => "prose", Prose, "Prose", "Проза";
+    ProseClassic => "prose_classic", Prose, "Classic prose", "Классическая проза";
+    ProseContemporary => "prose_contemporary", Prose, "Contemporary prose", "Современная проза";
+    ProseCounter => "prose_counter", Prose, "Counterculture", "Контркультура";
+    ProseHistory => "prose_history", Prose, "Historical prose", "Историческая проза";
+    ProseMilitary => "prose_military", Prose, "Military prose", "Военная проза";
+    ProseRusClassic => "prose_rus_classic", Prose, "Russian classic prose", "Русская классическая проза";
+    ProseSuClassics => "prose_su_classics", Prose, "Soviet classic prose", "Советская классическая проза";
+    PsyAlassic => "psy_alassic", Science, "Classic psychology", "Классики психологии";
+    PsyChilds => "psy_childs", Science, "Child psychology", "Детская психология";
+    PsyGeneric => "psy_generic", Science, "General psychology", "Общая психология";
+    PsyPersonal => "psy_personal", Science, "Personal growth", "Личностный рост";
+    PsySexAndFamily => "psy_sex_and_family", Science, "Sex and family", "Секс и семейная психология";
+    PsySocial => "psy_social", Science, "Social psychology", "Социальная психология";
+    PsyTheraphy => "psy_theraphy", Science, "Psychotherapy", "Психотерапия и консультирование";
+    RealEstate => "real_estate", Business, "Real estate", "Недвижимость";
+    RefDict => "ref_dict", Reference, "Dictionaries", "Словари";
+    RefEncyc => "ref_encyc", Reference, "Encyclopedias", "Энциклопедии";
+    RefGuide => "ref_guide", Reference, "Guides", "Руководства";
+    RefRef => "ref_ref", Reference, "Reference books", "Справочники";
+    Reference => "reference", Reference, "Reference", "Справочная литература";
+    Religion => "religion", Religion, "Religion", "Религия";
+    ReligionBudda => "religion_budda", Religion, "Buddhism", "Буддизм";
+    ReligionEsoterics => "religion_esoterics", Religion, "Esoterics", "Эзотерика";
+    ReligionRel => "religion_rel", Religion, "Religious studies", "Религиоведение";
+    ReligionSelf => "religion_self", Religion, "Self-improvement", "Самосовершенствование";
+    RomanceContemporary => "romance_contemporary", Romance, "Contemporary romance", "Современные любовные романы";
+    RomanceFantasy => "romance_fantasy", Romance, "Fantasy romance", "Любовное фэнтези";
+    RomanceHistorical => "romance_historical", Romance, "Historical romance", "Исторические любовные романы";
+    RomanceRomanticSuspense => "romance_romantic_suspense", Romance, "Romantic suspense", "Остросюжетные любовные романы";
+    RomanceSf => "romance_sf", Romance, "Science fiction romance", "Любовная фантастика";
+    RussianContemporary => "russian_contemporary", Prose, "Russian contemporary prose", "Русская современная проза";
+    RussianFantasy => "russian_fantasy", ScienceFiction, "Russian fantasy", "Славянское фэнтези";
+    SciBiology => "sci_biology", Science, "Biology", "Биология";
+    SciBusiness => "sci_business", Business, "Business literature", "Деловая литература";
+    SciChem => "sci_chem", Science, "Chemistry", "Химия";
+    SciCosmos => "sci_cosmos", Science, "Astronomy and space", "Астрономия и космос";
+    SciCulture => "sci_culture", Science, "Cultural studies", "Культурология";
+    SciEconomy => "sci_economy", Science, "Economics", "Экономика";
+    SciHistory => "sci_history", Science, "History", "История";
+    SciJuris => "sci_juris", Science, "Law", "Юриспруденция";
+    SciLinguistic => "sci_linguistic", Science, "Linguistics", "Языкознание";
+    SciMath => "sci_math", Science, "Mathematics", "Математика";
+    SciMedicine => "sci_medicine", Science, "Medicine", "Медицина";
+    SciPhilology => "sci_philology", Science, "Philology", "Филология";
+    SciPhilosophy => "sci_philosophy", Science, "Philosophy", "Философия";
+    SciPhys => "sci_phys", Science, "Physics", "Физика";
+    SciPolitics => "sci_politics", Science, "Political science", "Политология";
+    SciPsychology => "sci_psychology", Science, "Psychology", "Психология";
+    SciRadio => "sci_radio", Science, "Radio electronics", "Радиоэлектроника";
+    SciReligion => "sci_religion", Religion, "Religious studies", "Религиоведение";
+    SciState => "sci_state", Science, "State and law", "Государство и право";
+    SciTech => "sci_tech", Science, "Technical sciences", "Технические науки";
+    SciTransport => "sci_transport", Science, "Transport and aviation", "Транспорт и авиация";
+    Science => "science", Science, "Science", "Научная литература";
+    Sf => "sf", ScienceFiction, "Science fiction", "Научная фантастика";
+    SfAction => "sf_action", ScienceFiction, "Action science fiction", "Боевая фантастика";
+    SfCyberPunk => "sf_cyber_punk", ScienceFiction, "Cyberpunk", "Киберпанк";
+    SfCyberpunk => "sf_cyberpunk", ScienceFiction, "Cyberpunk", "Киберпанк";
+    SfDetective => "sf_detective", ScienceFiction, "Science fiction detective", "Детективная фантастика";
+    SfEpic => "sf_epic", ScienceFiction, "Epic fantasy", "Эпическая фантастика";
+    SfFantasy => "sf_fantasy", ScienceFiction, "Fantasy", "Фэнтези";
+    SfFantasyCity => "sf_fantasy_city", ScienceFiction, "Urban fantasy", "Городское фэнтези";
+    SfHeroic => "sf_heroic", ScienceFiction, "Heroic fantasy", "Героическая фантастика";
+    SfHistory => "sf_history", ScienceFiction, "Alternate history", "Альтернативная история";
+    SfHorror => "sf_horror", ScienceFiction, "Horror", "Ужасы";
+    SfHumor => "sf_humor", ScienceFiction, "Humorous science fiction", "Юмористическая фантастика";
+    SfMystic => "sf_mystic", ScienceFiction, "Mysticism", "Мистика";
+    SfPostapocalyptic => "sf_postapocalyptic", ScienceFiction, "Post-apocalyptic", "Постапокалипсис";
+    SfSocial => "sf_social", ScienceFiction, "Social science fiction", "Социальная фантастика";
+    SfSpace => "sf_space", ScienceFiction, "Space fiction", "Космическая фантастика";
+    SfWriting => "sf_writing", ScienceFiction, "Writing", "Писательское ремесло";
+    ShortStory => "short_story", Prose, "Short stories", "Рассказы";
+    Sketch => "sketch", Prose, "Sketches", "Очерки";
+    SmallBusiness => "small_business", Business, "Small business", "Малый бизнес";
+    SociologyBook => "sociology_book", Science, "Sociology", "Социология";
+    Stock => "stock", Business, "Stock market", "Ценные бумаги, инвестиции";
+    Thriller => "thriller", Detective, "Thriller", "Триллер";
+    ThrillerMystery => "thriller_mystery", Detective, "Mystery thriller", "Мистический триллер";
+    UpbringingBook => "upbringing_book", Home, "Upbringing", "Воспитание детей";
+    VampireBook => "vampire_book", ScienceFiction, "Vampires", "Вампиры";
+    VisualArts => "visual_arts", Art, "Visual arts", "Изобразительное искусство";
+}
+
+impl From<Genre> for String {
+    fn from(genre: Genre) -> Self {
+        match genre {
+            Genre::Other(code) => code,
+            genre => genre.code().to_string(),
+        }
+    }
+}
@@ -0,0 +1,577 @@
+//! Images of a book and the binaries they refer to.
+//!
+//! An image refers to a binary by a local link, e.g. `#cover.jpg` for `<binary id="cover.jpg">`.
+//! Real books are sloppy about it, so a link without `#`, in a different case, or URL-encoded
+//! still finds its binary. New binaries get ids no other binary has even in another case,
+//! so the sloppy links keep finding the same binaries.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    base64, Annotation, AnnotationElement, Binary, Body, Cite, CiteElement, Covers, Description,
+    DocumentInfo, Epigraph, EpigraphElement, Error, FictionBook, Image, ImageFormat, InlineImage,
+    Link, NamedStyle, Paragraph, Poem, PoemStanza, Section, SectionContent, SectionPart, Stanza,
+    Style, StyleElement, StyleLinkElement, Table, TableCell, TableCellElement, TableRow, Title,
+    TitleElement, TitleInfo,
+};
+
+/// An image of a book
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageRef<'a> {
+    /// An image between paragraphs, e.g. of a section
+    Image(&'a Image),
+    /// An image inside a paragraph, or a cover
+    Inline(&'a InlineImage),
+}
+
+impl<'a> ImageRef<'a> {
+    pub fn href(&self) -> Option<&'a str> {
+        match self {
+            ImageRef::Image(image) => image.href.as_deref(),
+            ImageRef::Inline(image) => image.href.as_deref(),
+        }
+    }
+}
+
+/// An image of a book that can be changed
+#[derive(Debug)]
+pub(crate) enum ImageMut<'a> {
+    Image(&'a mut Image),
+    Inline(&'a mut InlineImage),
+}
+
+impl ImageMut<'_> {
+    pub(crate) fn href(&mut self) -> &mut Option<String> {
+        match self {
+            ImageMut::Image(image) => &mut image.href,
+            ImageMut::Inline(image) => &mut image.href,
+        }
+    }
+}
+
+/// An image and the binary it refers to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResolvedImage<'a> {
+    pub image: ImageRef<'a>,
+    /// `None` if the image has no link, or no binary has the id
+    pub binary: Option<&'a Binary>,
+}
+
+impl FictionBook {
+    /// Images of the covers, annotations, and bodies, in the document order
+    pub fn images(&self) -> Vec<ImageRef<'_>> {
+        let mut found = Found::default();
+        self.walk(&mut found);
+        found.images
+    }
+
+    pub(crate) fn images_mut(&mut self) -> Vec<ImageMut<'_>> {
+        let mut images = vec![];
+        self.walk_mut(&mut images);
+        images
+    }
+
+    /// Finds the binary a link refers to, e.g. `#cover.jpg`. The `#` may be missing,
+    /// and the id may differ in case or be URL-encoded if no binary matches exactly
+    pub fn find_binary(&self, href: &str) -> Option<&Binary> {
+        BinaryIndex::new(&self.binaries).find(href)
+    }
+
+    /// Images together with their binaries, in the document order
+    pub fn resolve_images(&self) -> Vec<ResolvedImage<'_>> {
+        let index = BinaryIndex::new(&self.binaries);
+        self.images()
+            .into_iter()
+            .map(|image| ResolvedImage {
+                image,
+                binary: image.href().and_then(|href| index.find(href)),
+            })
+            .collect()
+    }
+
+    /// Images that refer to no binary, or have no link at all
+    pub fn dangling_images(&self) -> Vec<ImageRef<'_>> {
+        self.resolve_images()
+            .into_iter()
+            .filter(|resolved| resolved.binary.is_none())
+            .map(|resolved| resolved.image)
+            .collect()
+    }
+}
+
+impl FictionBook {
+    /// Adds a binary, its content type is told by the content, see [`ImageFormat::sniff`],
+    /// or else it is `application/octet-stream`.
+    /// The id is made unique with a suffix, e.g. `cover-2.jpg` if `cover.jpg` is taken,
+    /// and the whitespaces are replaced. Returns the id, the images link to it as `#id`
+    pub fn add_binary(&mut self, id: &str, content: &[u8]) -> String {
+        self.add_binary_with_content_type(id, content, sniffed_content_type(content))
+    }
+
+    /// Adds a binary of a content type, e.g. `application/pdf` for an attachment,
+    /// see [`add_binary`](Self::add_binary)
+    pub fn add_binary_with_content_type(
+        &mut self,
+        id: &str,
+        content: &[u8],
+        content_type: &str,
+    ) -> String {
+        let id = unique_id(&self.binaries, None, id);
+        self.binaries.push(Binary {
+            id: id.clone(),
+            content_type: content_type.to_string(),
+            content: base64::encode(content),
+        });
+        id
+    }
+
+    /// Replaces the content of the binary with the id, the content type is told by
+    /// the new content. Returns the replaced binary
+    pub fn replace_binary(&mut self, id: &str, content: &[u8]) -> Option<Binary> {
+        self.replace_binary_with_content_type(id, content, sniffed_content_type(content))
+    }
+
+    /// Replaces the content and the content type of the binary with the id,
+    /// see [`replace_binary`](Self::replace_binary)
+    pub fn replace_binary_with_content_type(
+        &mut self,
+        id: &str,
+        content: &[u8],
+        content_type: &str,
+    ) -> Option<Binary> {
+        let binary = self.binaries.iter_mut().find(|binary| binary.id == id)?;
+        let replacement = Binary {
+            id: binary.id.clone(),
+            content_type: content_type.to_string(),
+            content: base64::encode(content),
+        };
+        Some(std::mem::replace(binary, replacement))
+    }
+
+    /// Removes the binary with the id, the images that refer to it are kept
+    pub fn remove_binary(&mut self, id: &str) -> Option<Binary> {
+        let position = self.binaries.iter().position(|binary| binary.id == id)?;
+        Some(self.binaries.remove(position))
+    }
+
+    /// Changes the id of a binary, the new id is made unique as by [`add_binary`](Self::add_binary).
+    /// With `update_links`, the images that refer to the binary, even sloppily, link to the new id.
+    /// Returns the new id
+    pub fn rename_binary(&mut self, id: &str, new_id: &str, update_links: bool) -> Option<String> {
+        let position = self.binaries.iter().position(|binary| binary.id == id)?;
+        let new_id = unique_id(&self.binaries, Some(position), new_id);
+        if update_links {
+            let index = BinaryIndex::new(&self.binaries);
+            let renamed = &self.binaries[position];
+            let linked: Vec<_> = self
+                .images()
+                .iter()
+                .map(|image| {
+                    image
+                        .href()
+                        .and_then(|href| index.find(href))
+                        .is_some_and(|binary| std::ptr::eq(binary, renamed))
+                })
+                .collect();
+            // both walks visit the images in the same order
+            for (mut image, linked) in self.images_mut().into_iter().zip(linked) {
+                if linked {
+                    *image.href() = Some(format!("#{new_id}"));
+                }
+            }
+        }
+        self.binaries[position].id.clone_from(&new_id);
+        Some(new_id)
+    }
+}
+
+/// Binaries removed from a book because nothing refers to them
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RemovedBinaries {
+    pub binaries: Vec<Binary>,
+    /// Length of their base64 content, the document is that much shorter
+    pub encoded_bytes: usize,
+}
+
+impl FictionBook {
+    /// Binaries no image or local link, e.g. `<a l:href="#file.pdf">`, of the covers, annotations,
+    /// or bodies refers to, e.g. left over from editing
+    pub fn orphan_binaries(&self) -> Vec<&Binary> {
+        let referenced = self.referenced_binaries();
+        self.binaries
+            .iter()
+            .zip(referenced)
+            .filter(|(_, referenced)| !referenced)
+            .map(|(binary, _)| binary)
+            .collect()
+    }
+
+    /// Removes the [orphan binaries](Self::orphan_binaries), the rest keep their order
+    pub fn remove_orphan_binaries(&mut self) -> RemovedBinaries {
+        let referenced = self.referenced_binaries();
+        let (kept, removed): (Vec<_>, Vec<_>) = std::mem::take(&mut self.binaries)
+            .into_iter()
+            .zip(referenced)
+            .partition(|(_, referenced)| *referenced);
+        self.binaries = kept.into_iter().map(|(binary, _)| binary).collect();
+        let binaries: Vec<_> = removed.into_iter().map(|(binary, _)| binary).collect();
+        RemovedBinaries {
+            encoded_bytes: binaries.iter().map(|binary| binary.content.len()).sum(),
+            binaries,
+        }
+    }
+
+    /// Tells for each binary whether an image or a local link refers to it
+    fn referenced_binaries(&self) -> Vec<bool> {
+        let index = BinaryIndex::new(&self.binaries);
+        let mut referenced = vec![false; self.binaries.len()];
+        let mut found = Found::default();
+        self.walk(&mut found);
+        let images = found.images.iter().filter_map(ImageRef::href);
+        let links = found.links.iter().filter_map(|link| link.href.as_deref());
+        // a link to a note or a site is not sloppy about `#`
+        let links = links.filter(|href| href.trim_start().starts_with('#'));
+        for href in images.chain(links) {
+            if let Some(position) = index.position(href) {
+                referenced[position] = true;
+            }
+        }
+        referenced
+    }
+}
+
+/// A decoded cover image
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cover {
+    pub content: Vec<u8>,
+    /// Content type told by the content, the declared one only if the format is unknown
+    pub content_type: String,
+}
+
+impl Cover {
+    pub(crate) fn decode(binary: &Binary) -> Result<Cover, Error> {
+        let content = binary.decode()?;
+        let content_type = match ImageFormat::sniff(&content) {
+            Some(format) => format.content_type().to_string(),
+            None => binary.content_type.clone(),
+        };
+        Ok(Cover {
+            content,
+            content_type,
+        })
+    }
+}
+
+impl FictionBook {
+    /// Decodes the [cover binary](Self::cover_binary)
+    pub fn cover(&self) -> Result<Option<Cover>, Error> {
+        self.cover_binary().map(Cover::decode).transpose()
+    }
+
+    /// Binary of the first cover page image that has one, or of the image of the first body
+    /// if there is no cover page or its images have no binaries
+    pub fn cover_binary(&self) -> Option<&Binary> {
+        let index = BinaryIndex::new(&self.binaries);
+        let covers = self.description.title_info.cover_page.iter();
+        let covers = covers.flat_map(|covers| &covers.images);
+        let body = self.bodies.first().and_then(|body| body.image.as_ref());
+        covers
+            .filter_map(|image| image.href.as_deref())
+            .chain(body.and_then(|image| image.href.as_deref()))
+            .find_map(|href| index.find(href))
+    }
+}
+
+/// Content type of a new binary unless it is given
+fn sniffed_content_type(content: &[u8]) -> &'static str {
+    ImageFormat::sniff(content).map_or("application/octet-stream", ImageFormat::content_type)
+}
+
+/// An id no other binary has, even in another case, except the one at `except`
+fn unique_id(binaries: &[Binary], except: Option<usize>, id: &str) -> String {
+    let taken: HashSet<_> = binaries
+        .iter()
+        .enumerate()
+        .filter(|(position, _)| Some(*position) != except)
+        .map(|(_, binary)| loose_id(&binary.id))
+        .collect();
+    let id = id.split_whitespace().collect::<Vec<_>>().join("_");
+    let id = if id.is_empty() {
+        "image".to_string()
+    } else {
+        id
+    };
+    if !taken.contains(&loose_id(&id)) {
+        return id;
+    }
+    let (stem, extension) = match id.rfind('.') {
+        Some(dot) if dot > 0 => id.split_at(dot),
+        _ => (id.as_str(), ""),
+    };
+    (2..)
+        .map(|suffix| format!("{stem}-{suffix}{extension}"))
+        .find(|candidate| !taken.contains(&loose_id(candidate)))
+        .expect("there are fewer binaries than suffixes")
+}
+
+/// Lookup of the binaries by the forms of their ids
+pub(crate) struct BinaryIndex<'a> {
+    binaries: &'a [Binary],
+    exact: HashMap<&'a str, usize>,
+    loose: HashMap<String, usize>,
+}
+
+impl<'a> BinaryIndex<'a> {
+    pub(crate) fn new(binaries: &'a [Binary]) -> Self {
+        let mut exact = HashMap::new();
+        let mut loose = HashMap::new();
+        // the first binary wins if the ids repeat
+        for (index, binary) in binaries.iter().enumerate().rev() {
+            exact.insert(binary.id.as_str(), index);
+            loose.insert(loose_id(&binary.id), index);
+        }
+        BinaryIndex {
+            binaries,
+            exact,
+            loose,
+        }
+    }
+
+    pub(crate) fn find(&self, href: &str) -> Option<&'a Binary> {
+        Some(&self.binaries[self.position(href)?])
+    }
+
+    /// Position of the binary a link refers to in the binaries
+    pub(crate) fn position(&self, href: &str) -> Option<usize> {
+        let id = link_id(href);
+        let position = self
+            .exact
+            .get(id)
+            .or_else(|| self.loose.get(&loose_id(id)))?;
+        Some(*position)
+    }
+}
+
+/// Id a local link refers to
+pub(crate) fn link_id(href: &str) -> &str {
+    let href = href.trim();
+    match href.split_once('#') {
+        Some((_, id)) => id,
+        None => href,
+    }
+}
+
+/// Decoded and lowercased id
+pub(crate) fn loose_id(id: &str) -> String {
+    percent_decode(id.trim()).to_lowercase()
+}
+
+/// Decodes `%XX` sequences, the id is kept as is unless the decoded one is UTF-8
+fn percent_decode(id: &str) -> String {
+    if !id.contains('%') {
+        return id.to_string();
+    }
+    let bytes = id.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| id.to_string())
+}
+
+/// Images and links of a book in the document order
+#[derive(Default)]
+struct Found<'a> {
+    images: Vec<ImageRef<'a>>,
+    links: Vec<&'a Link>,
+}
+
+/// Collects the images and links of a node, only the images can be changed
+trait Walk {
+    fn walk<'a>(&'a self, found: &mut Found<'a>);
+    fn walk_mut<'a>(&'a mut self, images: &mut Vec<ImageMut<'a>>);
+}
+
+impl<T: Walk> Walk for Option<T> {
+    fn walk<'a>(&'a self, found: &mut Found<'a>) {
+        if let Some(node) = self {
+            node.walk(found);
+        }
+    }
+
+    fn walk_mut<'a>(&'a mut self, images: &mut Vec<ImageMut<'a>>) {
+        if let Some(node) = self {
+            node.walk_mut(images);
+        }
+    }
+}
+
+impl<T: Walk> Walk for Vec<T> {
+    fn walk<'a>(&'a self, found: &mut Found<'a>) {
+        for node in self {
+            node.walk(found);
+        }
+    }
+
+    fn walk_mut<'a>(&'a mut self, images: &mut Vec<ImageMut<'a>>) {
+        for node in self {
+            node.walk_mut(images);
+        }
+    }
+}
+
+impl Walk for Image {
+    fn walk<'a>(&'a self, found: &mut Found<'a>) {
+        found.images.push(ImageRef::Image(self));
+    }
+
+    fn walk_mut<'a>(&'a mut self, images: &mut Vec<ImageMut<'a>>) {
+        images.push(ImageMut::Image(self));
+    }
+}
+
+impl Walk for InlineImage {
+    fn walk<'a>(&'a self, found: &mut Found<'a>) {
+        found.images.push(ImageRef::Inline(self));
+    }
+
+    fn walk_mut<'a>(&'a mut self, images: &mut Vec<ImageMut<'a>>) {
+        images.push(ImageMut::Inline(self));
+    }
+}
+
+/// Walks the fields of a struct in the given order
+macro_rules! walk_fields {
+    ($type:ty: $($field:ident),+) => {
+        impl Walk for $type {
+            fn walk<'a>(&'a self, found: &mut Found<'a>) {
+                $(self.$field.walk(found);)+
+            }
+
+            fn walk_mut<'a>(&'a mut self, images: &mut Vec<ImageMut<'a>>) {
+                $(self.$field.walk_mut(images);)+
+            }
+        }
+    };
+}
+
+/// Walks the content of the tuple variants of an enum, the other variants have no images
+macro_rules! walk_variants {
+    ($type:ident: $($variant:ident),+) => {
+        impl Walk for $type {
+            #[allow(unreachable_patterns)]
+            fn walk<'a>(&'a self, found: &mut Found<'a>) {
+                match self {
+                    $($type::$variant(node) => node.walk(found),)+
+                    _ => {}
+                }
+            }
+
+            #[allow(unreachable_patterns)]
+            fn walk_mut<'a>(&'a mut self, images: &mut Vec<ImageMut<'a>>) {
+                match self {
+                    $($type::$variant(node) => node.walk_mut(images),)+
+                    _ => {}
+                }
+            }
+        }
+    };
+}
+
+walk_fields!(FictionBook: description, bodies);
+walk_fields!(Description: title_info, src_title_info, document_info);
+walk_fields!(TitleInfo: annotation, cover_page);
+walk_fields!(DocumentInfo: history);
+walk_fields!(Covers: images);
+walk_fields!(Body: image, title, epigraphs, sections);
+walk_fields!(Section: content);
+walk_fields!(SectionContent: title, epigraphs, image, annotation, content, sections);
+walk_fields!(Annotation: elements);
+walk_fields!(Epigraph: elements, text_authors);
+walk_fields!(Cite: elements, text_authors);
+walk_fields!(Poem: title, epigraphs, stanzas, text_authors);
+walk_fields!(Stanza: title, subtitle, lines);
+walk_fields!(Title: elements);
+walk_fields!(Table: rows);
+walk_fields!(TableRow: cells);
+walk_fields!(TableCell: elements);
+walk_fields!(Paragraph: elements);
+walk_fields!(Style: elements);
+walk_fields!(NamedStyle: elements);
+
+walk_variants!(SectionPart: Paragraph, Poem, Subtitle, Cite, Table, Image);
+walk_variants!(AnnotationElement: Paragraph, Poem, Cite, Subtitle, Table);
+walk_variants!(EpigraphElement: Paragraph, Poem, Cite);
+walk_variants!(CiteElement: Paragraph, Poem, Subtitle, Table);
+walk_variants!(PoemStanza: Subtitle, Stanza);
+walk_variants!(TitleElement: Paragraph);
+walk_variants!(TableCellElement: Head, Data);
+walk_variants!(
+    StyleElement: Strong,
+    Emphasis,
+    Style,
+    Link,
+    Strikethrough,
+    Subscript,
+    Superscript,
+    Code,
+    Image
+);
+
+impl Walk for Link {
+    fn walk<'a>(&'a self, found: &mut Found<'a>) {
+        found.links.push(self);
+        self.elements.walk(found);
+    }
+
+    fn walk_mut<'a>(&'a mut self, images: &mut Vec<ImageMut<'a>>) {
+        self.elements.walk_mut(images);
+    }
+}
+
+impl Walk for StyleLinkElement {
+    fn walk<'a>(&'a self, found: &mut Found<'a>) {
+        use StyleLinkElement::*;
+        match self {
+            Strong { elements }
+            | Emphasis { elements }
+            | Style { elements }
+            | Strikethrough { elements }
+            | Subscript { elements }
+            | Superscript { elements }
+            | Code { elements } => elements.walk(found),
+            Image(image) => image.walk(found),
+            Text(_) => {}
+        }
+    }
+
+    fn walk_mut<'a>(&'a mut self, images: &mut Vec<ImageMut<'a>>) {
+        use StyleLinkElement::*;
+        match self {
+            Strong { elements }
+            | Emphasis { elements }
+            | Style { elements }
+            | Strikethrough { elements }
+            | Subscript { elements }
+            | Superscript { elements }
+            | Code { elements } => elements.walk_mut(images),
+            Image(image) => image.walk_mut(images),
+            Text(_) => {}
+        }
+    }
+}
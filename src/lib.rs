@@ -1,9 +1,54 @@
-use std::num::ParseIntError;
-
 use language_tags::LanguageTag;
 use serde::ser::{SerializeStructVariant, SerializeTupleVariant};
 use serde::{Deserialize, Serialize, Serializer};
 
+use extras::Owner;
+use location::Subject;
+
+pub use binary::{ContentTypeMismatch, ImageFormat, LazyBinary};
+pub use date::{Confidence, DateRange, IsoDate, PartialDate, Year};
+#[cfg(feature = "encoding")]
+pub use de::from_reader_with_encoding;
+pub use de::{
+    from_path, from_reader, from_reader_lazy, from_reader_strict, from_reader_with_comments,
+    from_reader_with_repairs, from_slice, from_str, from_str_strict, from_str_with_comments,
+    from_str_with_repairs, read_cover, read_description, read_description_with_cover,
+};
+pub use error::{Error, StructureError, SyntaxError};
+pub use extras::{ExtraElement, ExtraNode, Extras, Misc};
+pub use genre::{Genre, GenreGroup, Locale};
+pub use images::{Cover, ImageRef, RemovedBinaries, ResolvedImage};
+pub use location::Location;
+pub use reader::{BookItem, BookReader};
+pub use repair::{Repair, RepairAction};
+pub use sequence::{SequenceNumber, SequenceValue};
+pub use ser::{
+    to_string, to_writer, to_writer_with_options, LineEnding, WriteOptions, FB2_NAMESPACE,
+    XLINK_NAMESPACE,
+};
+pub use version::Version;
+
+mod base64;
+mod binary;
+mod date;
+mod de;
+#[cfg(feature = "encoding")]
+pub mod encoding;
+mod error;
+mod extras;
+mod genre;
+mod images;
+mod location;
+mod reader;
+mod repair;
+mod scan;
+mod sequence;
+mod ser;
+mod version;
+mod xlink;
+#[cfg(feature = "zip")]
+pub mod zip;
+
 mod defaults {
     use super::{Date, Genre, GenreWithMatch, HorizontalAlign, LocalizedText, VerticalAlign};
 
@@ -69,6 +114,7 @@ mod defaults {
 
 /// Root element
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(from = "FictionBookInternal")]
 pub struct FictionBook {
     /// This element contains an arbitrary stylesheet that is interpreted by a some processing programs,
     /// e.g. text/css stylesheets can be used by XSLT stylesheets to generate better looking html
@@ -86,6 +132,39 @@ pub struct FictionBook {
     /// only images are used.
     #[serde(default, rename = "binary")]
     pub binaries: Vec<Binary>,
+    /// Elements and attributes unknown to the model, e.g. of extensions, kept for serialization
+    #[serde(flatten, skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+#[derive(Deserialize)]
+struct FictionBookInternal {
+    #[serde(default, rename = "stylesheet")]
+    stylesheets: Vec<Stylesheet>,
+    description: Description,
+    #[serde(rename = "body")]
+    bodies: Vec<Body>,
+    #[serde(default, rename = "binary")]
+    binaries: Vec<Binary>,
+}
+
+impl From<FictionBookInternal> for FictionBook {
+    fn from(
+        FictionBookInternal {
+            stylesheets,
+            description,
+            bodies,
+            binaries,
+        }: FictionBookInternal,
+    ) -> Self {
+        FictionBook {
+            stylesheets,
+            description,
+            bodies,
+            binaries,
+            extras: extras::take(Owner::FictionBook),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -97,6 +176,7 @@ pub struct Stylesheet {
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(from = "DescriptionInternal")]
 pub struct Description {
     /// Generic information about the book
     #[serde(rename = "title-info")]
@@ -118,10 +198,53 @@ pub struct Description {
     /// parts should be sold and what price should be used
     #[serde(default)]
     pub output: Vec<ShareInstruction>,
+    /// Elements and attributes unknown to the model, e.g. of extensions, kept for serialization
+    #[serde(flatten, skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+#[derive(Deserialize)]
+struct DescriptionInternal {
+    #[serde(rename = "title-info")]
+    title_info: TitleInfo,
+    #[serde(rename = "src-title-info")]
+    src_title_info: Option<TitleInfo>,
+    #[serde(rename = "document-info")]
+    document_info: Option<DocumentInfo>,
+    #[serde(rename = "publish-info")]
+    publish_info: Option<PublishInfo>,
+    #[serde(default, rename = "custom-info")]
+    custom_info: Vec<CustomInfo>,
+    #[serde(default)]
+    output: Vec<ShareInstruction>,
+}
+
+impl From<DescriptionInternal> for Description {
+    fn from(
+        DescriptionInternal {
+            title_info,
+            src_title_info,
+            document_info,
+            publish_info,
+            custom_info,
+            output,
+        }: DescriptionInternal,
+    ) -> Self {
+        Description {
+            title_info,
+            src_title_info,
+            document_info,
+            publish_info,
+            custom_info,
+            output,
+            extras: extras::take(Owner::Description),
+        }
+    }
 }
 
 /// Book (as a book opposite a document) description
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(from = "TitleInfoInternal")]
 pub struct TitleInfo {
     /// Genre of this book, with the optional match percentage
     #[serde(rename = "genre", default = "defaults::genres")]
@@ -157,6 +280,65 @@ pub struct TitleInfo {
     /// Any sequences this book might be part of
     #[serde(default, rename = "sequence")]
     pub sequences: Vec<Sequence>,
+    /// Elements and attributes unknown to the model, e.g. of extensions, kept for serialization
+    #[serde(flatten, skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
+}
+
+#[derive(Deserialize)]
+struct TitleInfoInternal {
+    #[serde(rename = "genre", default = "defaults::genres")]
+    genres: Vec<GenreWithMatch>,
+    #[serde(default, rename = "author")]
+    authors: Vec<Author>,
+    #[serde(rename = "book-title")]
+    book_title: LocalizedText,
+    annotation: Option<Annotation>,
+    keywords: Option<LocalizedText>,
+    date: Option<Date>,
+    #[serde(rename = "coverpage")]
+    cover_page: Option<Covers>,
+    #[serde(default)]
+    lang: String,
+    #[serde(rename = "src-lang")]
+    src_lang: Option<String>,
+    #[serde(default, rename = "translator")]
+    translators: Vec<Author>,
+    #[serde(default, rename = "sequence")]
+    sequences: Vec<Sequence>,
+}
+
+impl From<TitleInfoInternal> for TitleInfo {
+    fn from(
+        TitleInfoInternal {
+            genres,
+            authors,
+            book_title,
+            annotation,
+            keywords,
+            date,
+            cover_page,
+            lang,
+            src_lang,
+            translators,
+            sequences,
+        }: TitleInfoInternal,
+    ) -> Self {
+        TitleInfo {
+            genres,
+            authors,
+            book_title,
+            annotation,
+            keywords,
+            date,
+            cover_page,
+            lang,
+            src_lang,
+            translators,
+            sequences,
+            extras: extras::take(Owner::TitleInfo),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -191,7 +373,7 @@ pub struct DocumentInfo {
     /// Document version, in free format, should be incremented if the document is
     /// changed and re-released to the public
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub version: Option<f64>,
+    pub version: Option<Version>,
     /// Short description for all changes made to this document, like "Added
     /// missing chapter 6", in free form.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -213,7 +395,7 @@ struct DocumentInfoInternal {
     #[serde(rename = "src-ocr")]
     src_ocr: Option<LocalizedText>,
     id: Option<String>,
-    version: Option<String>,
+    version: Option<Version>,
     history: Option<Annotation>,
     #[serde(default, rename = "publisher")]
     publishers: Vec<Author>,
@@ -233,7 +415,12 @@ impl From<DocumentInfoInternal> for DocumentInfo {
             publishers,
         }: DocumentInfoInternal,
     ) -> Self {
-        let version = version.and_then(|v| v.parse().ok());
+        if version
+            .as_ref()
+            .is_some_and(|version| version.parts().is_none())
+        {
+            repair::record(Subject::Named("version"), RepairAction::InvalidValue);
+        }
         DocumentInfo {
             authors,
             program_used,
@@ -265,7 +452,7 @@ pub struct PublishInfo {
     pub city: Option<LocalizedText>,
     /// Year of the original (paper) publication
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub year: Option<i32>,
+    pub year: Option<Year>,
     #[serde(skip_serializing_if = "defaults::should_skip_serializing_text")]
     pub isbn: Option<LocalizedText>,
     #[serde(rename = "sequence")]
@@ -278,7 +465,7 @@ struct PublishInfoInternal {
     book_name: Option<LocalizedText>,
     publisher: Option<LocalizedText>,
     city: Option<LocalizedText>,
-    year: Option<String>,
+    year: Option<Year>,
     isbn: Option<LocalizedText>,
     #[serde(default, rename = "sequence")]
     sequences: Vec<Sequence>,
@@ -295,7 +482,9 @@ impl From<PublishInfoInternal> for PublishInfo {
             sequences,
         }: PublishInfoInternal,
     ) -> Self {
-        let year = year.and_then(|year| year.parse().ok());
+        if year.as_ref().is_some_and(|year| year.value().is_none()) {
+            repair::record(Subject::Named("year"), RepairAction::InvalidValue);
+        }
         PublishInfo {
             book_name,
             publisher,
@@ -464,30 +653,31 @@ impl From<BodyInternal> for Body {
             elements,
         }: BodyInternal,
     ) -> Self {
-        let mut iter = elements.into_iter();
+        let mut iter = elements.into_iter().enumerate();
         let mut element = iter.next();
 
-        let mut image = if let Some(BodyChoice::Image(image)) = element {
+        let mut image = if let Some((_, BodyChoice::Image(image))) = element {
             element = iter.next();
             Some(image)
         } else {
             None
         };
-        let mut title = if let Some(BodyChoice::Title(title)) = element {
+        let mut title = if let Some((_, BodyChoice::Title(title))) = element {
             element = iter.next();
             Some(title)
         } else {
             None
         };
         let mut epigraphs = vec![];
-        while let Some(BodyChoice::Epigraph(e)) = element {
+        while let Some((_, BodyChoice::Epigraph(e))) = element {
             epigraphs.push(e);
             element = iter.next();
         }
         let mut sections = vec![];
 
-        if let Some(element) = element {
+        if let Some((index, element)) = element {
             process_body_element(
+                index,
                 element,
                 &mut image,
                 &mut title,
@@ -495,8 +685,9 @@ impl From<BodyInternal> for Body {
                 &mut sections,
             );
         }
-        for element in iter {
+        for (index, element) in iter {
             process_body_element(
+                index,
                 element,
                 &mut image,
                 &mut title,
@@ -517,6 +708,7 @@ impl From<BodyInternal> for Body {
 }
 
 fn process_body_element(
+    index: usize,
     element: BodyChoice,
     image: &mut Option<Image>,
     title: &mut Option<Title>,
@@ -524,8 +716,22 @@ fn process_body_element(
     sections: &mut Vec<Section>,
 ) {
     match element {
-        BodyChoice::Body(b) => sections.extend(b.sections),
+        BodyChoice::Image(i) if sections.is_empty() && image.is_none() => *image = Some(i),
+        BodyChoice::Title(t) if sections.is_empty() && title.is_none() => *title = Some(t),
+        BodyChoice::Epigraph(e) if sections.is_empty() => epigraphs.push(e),
+        element => push_body_sections(index, element, sections),
+    }
+}
+
+/// Places an element of a body after the header into the sections
+fn push_body_sections(index: usize, element: BodyChoice, sections: &mut Vec<Section>) {
+    match element {
+        BodyChoice::Body(b) => {
+            repair::unwrapped(index);
+            sections.extend(b.sections)
+        }
         BodyChoice::Annotation(a) => {
+            repair::converted(index, "section");
             let content = a
                 .elements
                 .into_iter()
@@ -542,158 +748,90 @@ fn process_body_element(
                 id: a.id,
                 lang: a.lang,
                 content: Some(SectionContent {
-                    title: None,
-                    epigraphs: vec![],
-                    image: None,
-                    annotation: None,
                     content,
-                    sections: vec![],
+                    ..SectionContent::empty()
                 }),
+                extras: Extras::default(),
             });
         }
-        BodyChoice::Image(i) => {
-            if sections.is_empty() && image.is_none() {
-                *image = Some(i);
-            } else {
-                sections.push(Section {
-                    id: None,
-                    lang: None,
-                    content: Some(SectionContent {
-                        title: None,
-                        epigraphs: vec![],
-                        image: Some(i),
-                        annotation: None,
-                        content: vec![],
-                        sections: vec![],
-                    }),
-                })
-            }
+        BodyChoice::Image(i) => sections.push(wrap_into_section(
+            index,
+            SectionContent {
+                image: Some(i),
+                ..SectionContent::empty()
+            },
+        )),
+        BodyChoice::Title(t) => sections.push(wrap_into_section(
+            index,
+            SectionContent {
+                title: Some(t),
+                ..SectionContent::empty()
+            },
+        )),
+        BodyChoice::Epigraph(e) => sections.push(wrap_into_section(
+            index,
+            SectionContent {
+                epigraphs: vec![e],
+                ..SectionContent::empty()
+            },
+        )),
+        BodyChoice::Section(s) => sections.push(s),
+        BodyChoice::Paragraph(p) => {
+            sections.push(wrap_part_into_section(index, SectionPart::Paragraph(p)))
         }
-        BodyChoice::Title(t) => {
-            if sections.is_empty() && title.is_none() {
-                *title = Some(t);
-            } else {
-                sections.push(Section {
-                    id: None,
-                    lang: None,
-                    content: Some(SectionContent {
-                        title: Some(t),
-                        epigraphs: vec![],
-                        image: None,
-                        annotation: None,
-                        content: vec![],
-                        sections: vec![],
-                    }),
-                });
-            }
+        BodyChoice::Subtitle(s) => {
+            sections.push(wrap_part_into_section(index, SectionPart::Subtitle(s)))
         }
-        BodyChoice::Epigraph(e) => {
-            if sections.is_empty() {
-                epigraphs.push(e);
-            } else {
-                sections.push(Section {
-                    id: None,
-                    lang: None,
-                    content: Some(SectionContent {
-                        title: None,
-                        epigraphs: vec![e],
-                        image: None,
-                        annotation: None,
-                        content: vec![],
-                        sections: vec![],
-                    }),
-                });
-            }
+        BodyChoice::Cite(c) => sections.push(wrap_part_into_section(index, SectionPart::Cite(c))),
+        BodyChoice::Poem(p) => sections.push(wrap_part_into_section(index, SectionPart::Poem(p))),
+        BodyChoice::EmptyLine => {
+            sections.push(wrap_part_into_section(index, SectionPart::EmptyLine))
         }
-        BodyChoice::Section(s) => sections.push(s),
-        BodyChoice::Paragraph(p) => sections.push(Section {
-            id: None,
-            lang: None,
-            content: Some(SectionContent {
-                title: None,
-                epigraphs: vec![],
-                image: None,
-                annotation: None,
-                content: vec![SectionPart::Paragraph(p)],
-                sections: vec![],
-            }),
-        }),
-        BodyChoice::Subtitle(s) => sections.push(Section {
-            id: None,
-            lang: None,
-            content: Some(SectionContent {
-                title: None,
-                epigraphs: vec![],
-                image: None,
-                annotation: None,
-                content: vec![SectionPart::Subtitle(s)],
-                sections: vec![],
-            }),
-        }),
-        BodyChoice::Cite(c) => sections.push(Section {
-            id: None,
-            lang: None,
-            content: Some(SectionContent {
-                title: None,
-                epigraphs: vec![],
-                image: None,
-                annotation: None,
-                content: vec![SectionPart::Cite(c)],
-                sections: vec![],
-            }),
-        }),
-        BodyChoice::Poem(p) => sections.push(Section {
-            id: None,
-            lang: None,
-            content: Some(SectionContent {
-                title: None,
-                epigraphs: vec![],
-                image: None,
-                annotation: None,
-                content: vec![SectionPart::Poem(p)],
-                sections: vec![],
-            }),
-        }),
-        BodyChoice::EmptyLine => sections.push(Section {
-            id: None,
-            lang: None,
-            content: Some(SectionContent {
-                title: None,
-                epigraphs: vec![],
-                image: None,
-                annotation: None,
-                content: vec![SectionPart::EmptyLine],
-                sections: vec![],
-            }),
-        }),
-        BodyChoice::Text(t) => sections.push(Section {
-            id: None,
-            lang: None,
-            content: Some(SectionContent {
-                title: None,
-                epigraphs: vec![],
-                image: None,
-                annotation: None,
-                content: vec![SectionPart::Paragraph(Paragraph {
-                    id: None,
-                    lang: None,
-                    style: None,
-                    elements: vec![StyleElement::Text(t)],
-                })],
-                sections: vec![],
-            }),
-        }),
+        BodyChoice::Text(t) => {
+            let paragraph = Paragraph {
+                id: None,
+                lang: None,
+                style: None,
+                elements: vec![StyleElement::Text(t)],
+                extras: Extras::default(),
+            };
+            sections.push(wrap_part_into_section(
+                index,
+                SectionPart::Paragraph(paragraph),
+            ))
+        }
+    }
+}
+
+/// Wraps a stray element of a body into a section of its own, and records the repair
+fn wrap_into_section(index: usize, content: SectionContent) -> Section {
+    repair::wrapped(index, "section");
+    Section {
+        id: None,
+        lang: None,
+        content: Some(content),
+        extras: Extras::default(),
     }
 }
 
+fn wrap_part_into_section(index: usize, part: SectionPart) -> Section {
+    wrap_into_section(
+        index,
+        SectionContent {
+            content: vec![part],
+            ..SectionContent::empty()
+        },
+    )
+}
+
 /// Book sequences
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
-#[serde(try_from = "SequenceInternal")]
+#[serde(from = "SequenceInternal")]
 pub struct Sequence {
     #[serde(rename = "@name")]
     pub name: Option<String>,
     #[serde(rename = "@number", skip_serializing_if = "Option::is_none")]
-    pub number: Option<i32>,
+    pub number: Option<SequenceNumber>,
     #[serde(rename = "@lang", skip_serializing_if = "Option::is_none")]
     pub lang: Option<LanguageTag>,
     #[serde(rename = "sequence")]
@@ -705,35 +843,34 @@ struct SequenceInternal {
     #[serde(rename = "@name")]
     name: Option<String>,
     #[serde(rename = "@number")]
-    number: Option<String>,
+    number: Option<SequenceNumber>,
     #[serde(rename = "@lang")]
     lang: Option<LanguageTag>,
     #[serde(default, rename = "sequence")]
     sequences: Vec<Sequence>,
 }
 
-impl TryFrom<SequenceInternal> for Sequence {
-    type Error = ParseIntError;
-
-    fn try_from(
+impl From<SequenceInternal> for Sequence {
+    fn from(
         SequenceInternal {
             name,
             number,
             lang,
             sequences,
         }: SequenceInternal,
-    ) -> Result<Self, Self::Error> {
-        let number = if let Some(n) = number {
-            Some(n.trim().parse()?)
-        } else {
-            None
-        };
-        Ok(Sequence {
+    ) -> Self {
+        if number
+            .as_ref()
+            .is_some_and(|number| number.value().is_none())
+        {
+            repair::record(Subject::Attribute("number"), RepairAction::InvalidValue);
+        }
+        Sequence {
             name,
             number,
             lang,
             sequences,
-        })
+        }
     }
 }
 
@@ -765,7 +902,13 @@ impl From<GenreWithMatchInternal> for GenreWithMatch {
         }: GenreWithMatchInternal,
     ) -> Self {
         let match_percentage = match_percentage
-            .and_then(|m| m.parse().ok())
+            .and_then(|m| {
+                let percentage = m.parse().ok();
+                if percentage.is_none() {
+                    repair::record(Subject::Attribute("match"), RepairAction::InvalidValue);
+                }
+                percentage
+            })
             .unwrap_or(defaults::DEFAULT_GENRE_MATCH);
         GenreWithMatch {
             match_percentage,
@@ -783,6 +926,8 @@ struct GenreWithMatchInternal {
 }
 
 /// Information about a single author
+// boxing the larger variant would change the public model
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, PartialEq, Deserialize)]
 #[serde(from = "AuthorInternal")]
 pub enum Author {
@@ -921,6 +1066,9 @@ pub struct Section {
     pub lang: Option<LanguageTag>,
     #[serde(flatten, rename = "$value", skip_serializing_if = "Option::is_none")]
     pub content: Option<SectionContent>,
+    /// Elements and attributes unknown to the model, e.g. of extensions, kept for serialization
+    #[serde(flatten, skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize)]
@@ -943,6 +1091,19 @@ pub struct SectionContent {
     pub sections: Vec<Section>,
 }
 
+impl SectionContent {
+    fn empty() -> Self {
+        SectionContent {
+            title: None,
+            epigraphs: vec![],
+            image: None,
+            annotation: None,
+            content: vec![],
+            sections: vec![],
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum SectionPart {
     #[serde(rename = "p")]
@@ -975,6 +1136,7 @@ struct SectionInternal {
 #[derive(Deserialize)]
 enum SectionChoice {
     // tricky to encode in a single section, skipping
+    #[allow(dead_code)]
     #[serde(rename = "body")]
     Body(Body),
     #[serde(rename = "title")]
@@ -1057,33 +1219,35 @@ enum SectionChoice {
 
 impl From<SectionInternal> for Section {
     fn from(SectionInternal { id, lang, elements }: SectionInternal) -> Self {
+        let extras = extras::take(Owner::Section);
         if elements.is_empty() {
             return Section {
                 id,
                 lang,
                 content: None,
+                extras,
             };
         }
-        let mut iter = elements.into_iter();
+        let mut iter = elements.into_iter().enumerate();
         let mut element = iter.next();
-        let mut title = if let Some(SectionChoice::Title(t)) = element {
+        let mut title = if let Some((_, SectionChoice::Title(t))) = element {
             element = iter.next();
             Some(t)
         } else {
             None
         };
         let mut epigraphs = vec![];
-        while let Some(SectionChoice::Epigraph(e)) = element {
+        while let Some((_, SectionChoice::Epigraph(e))) = element {
             epigraphs.push(e);
             element = iter.next();
         }
-        let mut image = if let Some(SectionChoice::Image(i)) = element {
+        let mut image = if let Some((_, SectionChoice::Image(i))) = element {
             element = iter.next();
             Some(i)
         } else {
             None
         };
-        let mut annotation = if let Some(SectionChoice::Annotation(a)) = element {
+        let mut annotation = if let Some((_, SectionChoice::Annotation(a))) = element {
             element = iter.next();
             Some(a)
         } else {
@@ -1092,8 +1256,9 @@ impl From<SectionInternal> for Section {
         let mut sections = Vec::new();
         let mut content = Vec::new();
 
-        if let Some(element) = element {
+        if let Some((index, element)) = element {
             process_section_element(
+                index,
                 element,
                 &mut title,
                 &mut epigraphs,
@@ -1103,8 +1268,9 @@ impl From<SectionInternal> for Section {
                 &mut content,
             );
         }
-        for element in iter {
+        for (index, element) in iter {
             process_section_element(
+                index,
                 element,
                 &mut title,
                 &mut epigraphs,
@@ -1126,11 +1292,14 @@ impl From<SectionInternal> for Section {
                 content,
                 sections,
             }),
+            extras,
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn process_section_element(
+    index: usize,
     element: SectionChoice,
     title: &mut Option<Title>,
     epigraphs: &mut Vec<Epigraph>,
@@ -1141,11 +1310,12 @@ fn process_section_element(
 ) {
     match element {
         // tricky to encode in a single section, skipping
-        SectionChoice::Body(_) => {}
+        SectionChoice::Body(_) => repair::dropped(index),
         SectionChoice::Title(t) => {
             if content.is_empty() && title.is_none() {
                 *title = Some(t);
             } else {
+                repair::unwrapped(index);
                 for element in t.elements {
                     match element {
                         TitleElement::Paragraph(p) => content.push(SectionPart::Paragraph(p)),
@@ -1158,6 +1328,7 @@ fn process_section_element(
             if content.is_empty() {
                 epigraphs.push(e);
             } else {
+                repair::unwrapped(index);
                 for element in e.elements {
                     match element {
                         EpigraphElement::Paragraph(p) => content.push(SectionPart::Paragraph(p)),
@@ -1179,6 +1350,7 @@ fn process_section_element(
             if content.is_empty() && annotation.is_none() {
                 *annotation = Some(a);
             } else {
+                repair::unwrapped(index);
                 for element in a.elements {
                     match element {
                         AnnotationElement::Paragraph(p) => content.push(SectionPart::Paragraph(p)),
@@ -1198,81 +1370,126 @@ fn process_section_element(
         SectionChoice::Cite(c) => content.push(SectionPart::Cite(c)),
         SectionChoice::Table(t) => content.push(SectionPart::Table(t)),
         SectionChoice::EmptyLine => content.push(SectionPart::EmptyLine),
-        SectionChoice::Stanza(s) => content.push(SectionPart::Poem(Poem {
-            id: None,
-            lang: None,
-            title: None,
-            epigraphs: vec![],
-            stanzas: vec![PoemStanza::Stanza(s)],
-            text_authors: vec![],
-            date: None,
-        })),
-        SectionChoice::StanzaLine(s) => content.push(SectionPart::Paragraph(s)),
+        SectionChoice::Stanza(s) => {
+            repair::wrapped(index, "poem");
+            content.push(SectionPart::Poem(Poem {
+                id: None,
+                lang: None,
+                title: None,
+                epigraphs: vec![],
+                stanzas: vec![PoemStanza::Stanza(s)],
+                text_authors: vec![],
+                date: None,
+            }))
+        }
+        SectionChoice::StanzaLine(s) => {
+            repair::converted(index, "p");
+            content.push(SectionPart::Paragraph(s))
+        }
         // trying to fix invalid FB2 without losing information
-        SectionChoice::TextAuthor(p) => content.push(SectionPart::Paragraph(p)),
+        SectionChoice::TextAuthor(p) => {
+            repair::converted(index, "p");
+            content.push(SectionPart::Paragraph(p))
+        }
         // trying to fix invalid FB2 without losing information
-        SectionChoice::Strong(s) => content.push(SectionPart::Paragraph(Paragraph {
-            id: None,
-            lang: None,
-            style: None,
-            elements: vec![StyleElement::Strong(s)],
-        })),
+        SectionChoice::Strong(s) => {
+            repair::wrapped(index, "p");
+            content.push(SectionPart::Paragraph(Paragraph {
+                id: None,
+                lang: None,
+                style: None,
+                elements: vec![StyleElement::Strong(s)],
+                extras: Extras::default(),
+            }))
+        }
         // trying to fix invalid FB2 without losing information
-        SectionChoice::Emphasis(e) => content.push(SectionPart::Paragraph(Paragraph {
-            id: None,
-            lang: None,
-            style: None,
-            elements: vec![StyleElement::Emphasis(e)],
-        })),
+        SectionChoice::Emphasis(e) => {
+            repair::wrapped(index, "p");
+            content.push(SectionPart::Paragraph(Paragraph {
+                id: None,
+                lang: None,
+                style: None,
+                elements: vec![StyleElement::Emphasis(e)],
+                extras: Extras::default(),
+            }))
+        }
         // trying to fix invalid FB2 without losing information
-        SectionChoice::Style(s) => content.push(SectionPart::Paragraph(Paragraph {
-            id: None,
-            lang: None,
-            style: None,
-            elements: vec![StyleElement::Style(s)],
-        })),
+        SectionChoice::Style(s) => {
+            repair::wrapped(index, "p");
+            content.push(SectionPart::Paragraph(Paragraph {
+                id: None,
+                lang: None,
+                style: None,
+                elements: vec![StyleElement::Style(s)],
+                extras: Extras::default(),
+            }))
+        }
         // trying to fix invalid FB2 without losing information
-        SectionChoice::Link(link) => content.push(SectionPart::Paragraph(Paragraph {
-            id: None,
-            lang: None,
-            style: None,
-            elements: vec![StyleElement::Link(link)],
-        })),
+        SectionChoice::Link(link) => {
+            repair::wrapped(index, "p");
+            content.push(SectionPart::Paragraph(Paragraph {
+                id: None,
+                lang: None,
+                style: None,
+                elements: vec![StyleElement::Link(link)],
+                extras: Extras::default(),
+            }))
+        }
         // trying to fix invalid FB2 without losing information
-        SectionChoice::Strikethrough(s) => content.push(SectionPart::Paragraph(Paragraph {
-            id: None,
-            lang: None,
-            style: None,
-            elements: vec![StyleElement::Strikethrough(s)],
-        })),
+        SectionChoice::Strikethrough(s) => {
+            repair::wrapped(index, "p");
+            content.push(SectionPart::Paragraph(Paragraph {
+                id: None,
+                lang: None,
+                style: None,
+                elements: vec![StyleElement::Strikethrough(s)],
+                extras: Extras::default(),
+            }))
+        }
         // trying to fix invalid FB2 without losing information
-        SectionChoice::Subscript(s) => content.push(SectionPart::Paragraph(Paragraph {
-            id: None,
-            lang: None,
-            style: None,
-            elements: vec![StyleElement::Subscript(s)],
-        })),
+        SectionChoice::Subscript(s) => {
+            repair::wrapped(index, "p");
+            content.push(SectionPart::Paragraph(Paragraph {
+                id: None,
+                lang: None,
+                style: None,
+                elements: vec![StyleElement::Subscript(s)],
+                extras: Extras::default(),
+            }))
+        }
         // trying to fix invalid FB2 without losing information
-        SectionChoice::Superscript(s) => content.push(SectionPart::Paragraph(Paragraph {
-            id: None,
-            lang: None,
-            style: None,
-            elements: vec![StyleElement::Superscript(s)],
-        })),
+        SectionChoice::Superscript(s) => {
+            repair::wrapped(index, "p");
+            content.push(SectionPart::Paragraph(Paragraph {
+                id: None,
+                lang: None,
+                style: None,
+                elements: vec![StyleElement::Superscript(s)],
+                extras: Extras::default(),
+            }))
+        }
         // trying to fix invalid FB2 without losing information
-        SectionChoice::Code(c) => content.push(SectionPart::Paragraph(Paragraph {
-            id: None,
-            lang: None,
-            style: None,
-            elements: vec![StyleElement::Code(c)],
-        })),
+        SectionChoice::Code(c) => {
+            repair::wrapped(index, "p");
+            content.push(SectionPart::Paragraph(Paragraph {
+                id: None,
+                lang: None,
+                style: None,
+                elements: vec![StyleElement::Code(c)],
+                extras: Extras::default(),
+            }))
+        }
         // trying to fix invalid FB2 without losing information
-        SectionChoice::Text(text) => content.push(SectionPart::Paragraph(Paragraph {
-            id: None,
-            lang: None,
-            style: None,
-            elements: vec![StyleElement::Text(text)],
-        })),
+        SectionChoice::Text(text) => {
+            repair::wrapped(index, "p");
+            content.push(SectionPart::Paragraph(Paragraph {
+                id: None,
+                lang: None,
+                style: None,
+                elements: vec![StyleElement::Text(text)],
+                extras: Extras::default(),
+            }))
+        }
     }
 }
 
@@ -1318,6 +1535,7 @@ struct AnnotationInternal {
 #[derive(Deserialize)]
 enum AnnotationChoice {
     // tricky to encode in annotation, skipping
+    #[allow(dead_code)]
     #[serde(rename = "body")]
     Body(Body),
     #[serde(rename = "annotation")]
@@ -1357,11 +1575,14 @@ impl From<AnnotationInternal> for Annotation {
         }: AnnotationInternal,
     ) -> Self {
         let mut elements = Vec::with_capacity(choices.len());
-        for element in choices {
+        for (index, element) in choices.into_iter().enumerate() {
             match element {
                 // tricky to encode in annotation, skipping
-                AnnotationChoice::Body(_) => {}
-                AnnotationChoice::Annotation(a) => elements.extend(a.elements),
+                AnnotationChoice::Body(_) => repair::dropped(index),
+                AnnotationChoice::Annotation(a) => {
+                    repair::unwrapped(index);
+                    elements.extend(a.elements)
+                }
                 AnnotationChoice::Paragraph(p) => elements.push(AnnotationElement::Paragraph(p)),
                 AnnotationChoice::Poem(p) => elements.push(AnnotationElement::Poem(p)),
                 AnnotationChoice::Cite(c) => elements.push(AnnotationElement::Cite(c)),
@@ -1369,6 +1590,7 @@ impl From<AnnotationInternal> for Annotation {
                 AnnotationChoice::Table(t) => elements.push(AnnotationElement::Table(t)),
                 AnnotationChoice::EmptyLine => elements.push(AnnotationElement::EmptyLine),
                 AnnotationChoice::Title(t) => {
+                    repair::unwrapped(index);
                     for element in t.elements {
                         match element {
                             TitleElement::Paragraph(p) => {
@@ -1379,6 +1601,7 @@ impl From<AnnotationInternal> for Annotation {
                     }
                 }
                 AnnotationChoice::Stanza(s) => {
+                    repair::unwrapped(index);
                     if let Some(title) = s.title {
                         for element in title.elements {
                             match element {
@@ -1399,28 +1622,37 @@ impl From<AnnotationInternal> for Annotation {
                     }
                 }
                 AnnotationChoice::Image(i) => {
+                    repair::wrapped(index, "p");
                     elements.push(AnnotationElement::Paragraph(Paragraph {
                         id: None,
                         lang: None,
                         style: None,
                         elements: vec![StyleElement::Image(i)],
+                        extras: Extras::default(),
                     }))
                 }
                 AnnotationChoice::Italics(i) => {
+                    repair::wrapped(index, "p");
                     elements.push(AnnotationElement::Paragraph(Paragraph {
                         id: None,
                         lang: None,
                         style: None,
                         elements: vec![StyleElement::Emphasis(i)],
+                        extras: Extras::default(),
                     }))
                 }
-                AnnotationChoice::TextAuthor(p) => elements.push(AnnotationElement::Paragraph(p)),
+                AnnotationChoice::TextAuthor(p) => {
+                    repair::converted(index, "p");
+                    elements.push(AnnotationElement::Paragraph(p))
+                }
                 AnnotationChoice::Text(text) => {
+                    repair::wrapped(index, "p");
                     elements.push(AnnotationElement::Paragraph(Paragraph {
                         id: None,
                         lang: None,
                         style: None,
                         elements: vec![StyleElement::Text(text)],
+                        extras: Extras::default(),
                     }))
                 }
             }
@@ -1498,9 +1730,10 @@ impl From<EpigraphInternal> for Epigraph {
         }: EpigraphInternal,
     ) -> Self {
         let mut elements = Vec::with_capacity(choices.len());
-        for element in choices {
+        for (index, element) in choices.into_iter().enumerate() {
             match element {
                 EpigraphChoice::Epigraph(e) => {
+                    repair::unwrapped(index);
                     elements.extend(e.elements);
                     for text_author in e.text_authors {
                         elements.push(EpigraphElement::Paragraph(text_author));
@@ -1510,6 +1743,7 @@ impl From<EpigraphInternal> for Epigraph {
                 EpigraphChoice::Poem(p) => elements.push(EpigraphElement::Poem(p)),
                 EpigraphChoice::Cite(c) => elements.push(EpigraphElement::Cite(c)),
                 EpigraphChoice::Stanza(s) => {
+                    repair::unwrapped(index);
                     if let Some(title) = s.title {
                         for element in title.elements {
                             match element {
@@ -1530,28 +1764,44 @@ impl From<EpigraphInternal> for Epigraph {
                     }
                 }
                 EpigraphChoice::EmptyLine => elements.push(EpigraphElement::EmptyLine),
-                EpigraphChoice::Subtitle(s) => elements.push(EpigraphElement::Paragraph(s)),
-                EpigraphChoice::StanzaLine(s) => elements.push(EpigraphElement::Paragraph(s)),
+                EpigraphChoice::Subtitle(s) => {
+                    repair::converted(index, "p");
+                    elements.push(EpigraphElement::Paragraph(s))
+                }
+                EpigraphChoice::StanzaLine(s) => {
+                    repair::converted(index, "p");
+                    elements.push(EpigraphElement::Paragraph(s))
+                }
                 EpigraphChoice::Emphasis(e) => {
+                    repair::wrapped(index, "p");
                     elements.push(EpigraphElement::Paragraph(Paragraph {
                         id: None,
                         lang: None,
                         style: None,
                         elements: vec![StyleElement::Emphasis(e)],
+                        extras: Extras::default(),
+                    }))
+                }
+                EpigraphChoice::Image(i) => {
+                    repair::wrapped(index, "p");
+                    elements.push(EpigraphElement::Paragraph(Paragraph {
+                        id: None,
+                        lang: None,
+                        style: None,
+                        elements: vec![StyleElement::Image(i)],
+                        extras: Extras::default(),
+                    }))
+                }
+                EpigraphChoice::Text(t) => {
+                    repair::wrapped(index, "p");
+                    elements.push(EpigraphElement::Paragraph(Paragraph {
+                        id: None,
+                        lang: None,
+                        style: None,
+                        elements: vec![StyleElement::Text(t)],
+                        extras: Extras::default(),
                     }))
                 }
-                EpigraphChoice::Image(i) => elements.push(EpigraphElement::Paragraph(Paragraph {
-                    id: None,
-                    lang: None,
-                    style: None,
-                    elements: vec![StyleElement::Image(i)],
-                })),
-                EpigraphChoice::Text(t) => elements.push(EpigraphElement::Paragraph(Paragraph {
-                    id: None,
-                    lang: None,
-                    style: None,
-                    elements: vec![StyleElement::Text(t)],
-                })),
             }
         }
         Epigraph {
@@ -1631,24 +1881,33 @@ impl From<CiteInternal> for Cite {
     ) -> Self {
         let elements = elements
             .into_iter()
-            .map(|element| match element {
+            .enumerate()
+            .map(|(index, element)| match element {
                 CiteChoice::Paragraph(p) => CiteElement::Paragraph(p),
                 CiteChoice::Poem(p) => CiteElement::Poem(p),
                 CiteChoice::Subtitle(s) => CiteElement::Subtitle(s),
                 CiteChoice::Table(t) => CiteElement::Table(t),
                 CiteChoice::EmptyLine => CiteElement::EmptyLine,
-                CiteChoice::Image(i) => CiteElement::Paragraph(Paragraph {
-                    id: None,
-                    lang: None,
-                    style: None,
-                    elements: vec![StyleElement::Image(i)],
-                }),
-                CiteChoice::Text(t) => CiteElement::Paragraph(Paragraph {
-                    id: None,
-                    lang: None,
-                    style: None,
-                    elements: vec![StyleElement::Text(t)],
-                }),
+                CiteChoice::Image(i) => {
+                    repair::wrapped(index, "p");
+                    CiteElement::Paragraph(Paragraph {
+                        id: None,
+                        lang: None,
+                        style: None,
+                        elements: vec![StyleElement::Image(i)],
+                        extras: Extras::default(),
+                    })
+                }
+                CiteChoice::Text(t) => {
+                    repair::wrapped(index, "p");
+                    CiteElement::Paragraph(Paragraph {
+                        id: None,
+                        lang: None,
+                        style: None,
+                        elements: vec![StyleElement::Text(t)],
+                        extras: Extras::default(),
+                    })
+                }
             })
             .collect();
         Cite {
@@ -1705,26 +1964,39 @@ enum PoemStanzaInternal {
 impl From<PoemStanzaInternal> for PoemStanza {
     fn from(value: PoemStanzaInternal) -> Self {
         match value {
-            PoemStanzaInternal::Paragraph(p) => PoemStanza::Stanza(Stanza {
-                lang: None,
-                title: None,
-                subtitle: None,
-                lines: vec![p],
-            }),
+            PoemStanzaInternal::Paragraph(p) => {
+                repair::record(Subject::Element, RepairAction::Wrapped { into: "stanza" });
+                PoemStanza::Stanza(Stanza {
+                    lang: None,
+                    title: None,
+                    subtitle: None,
+                    lines: vec![p],
+                })
+            }
             PoemStanzaInternal::Subtitle(s) => PoemStanza::Subtitle(s),
             PoemStanzaInternal::Stanza(s) => PoemStanza::Stanza(s),
-            PoemStanzaInternal::StanzaLine(s) => PoemStanza::Stanza(Stanza {
-                lang: None,
-                title: None,
-                subtitle: None,
-                lines: vec![s],
-            }),
-            PoemStanzaInternal::EmptyLine => PoemStanza::Subtitle(Paragraph {
-                id: None,
-                lang: None,
-                style: None,
-                elements: vec![],
-            }),
+            PoemStanzaInternal::StanzaLine(s) => {
+                repair::record(Subject::Element, RepairAction::Wrapped { into: "stanza" });
+                PoemStanza::Stanza(Stanza {
+                    lang: None,
+                    title: None,
+                    subtitle: None,
+                    lines: vec![s],
+                })
+            }
+            PoemStanzaInternal::EmptyLine => {
+                repair::record(
+                    Subject::Element,
+                    RepairAction::Converted { into: "subtitle" },
+                );
+                PoemStanza::Subtitle(Paragraph {
+                    id: None,
+                    lang: None,
+                    style: None,
+                    elements: vec![],
+                    extras: Extras::default(),
+                })
+            }
         }
     }
 }
@@ -1758,6 +2030,8 @@ enum StanzaChoice {
     Stanza(Stanza),
     #[serde(rename = "p")]
     Paragraph(Paragraph),
+    // skipped because tricky to map to the stanza itself
+    #[allow(dead_code)]
     #[serde(rename = "epigraph")]
     Epigraph(Epigraph),
     #[serde(rename = "title")]
@@ -1780,27 +2054,27 @@ enum StanzaChoice {
 
 impl From<StanzaInternal> for Stanza {
     fn from(StanzaInternal { lang, elements }: StanzaInternal) -> Self {
-        let mut iter = elements.into_iter();
+        let mut iter = elements.into_iter().enumerate();
         let mut element = iter.next();
 
-        let mut title = if let Some(StanzaChoice::Title(t)) = element {
+        let mut title = if let Some((_, StanzaChoice::Title(t))) = element {
             element = iter.next();
             Some(t)
         } else {
             None
         };
-        let mut subtitle = if let Some(StanzaChoice::Subtitle(s)) = element {
+        let mut subtitle = if let Some((_, StanzaChoice::Subtitle(s))) = element {
             element = iter.next();
             Some(s)
         } else {
             None
         };
         let mut lines = vec![];
-        if let Some(element) = element {
-            process_stanza_element(element, &mut title, &mut subtitle, &mut lines);
+        if let Some((index, element)) = element {
+            process_stanza_element(index, element, &mut title, &mut subtitle, &mut lines);
         }
-        for element in iter {
-            process_stanza_element(element, &mut title, &mut subtitle, &mut lines);
+        for (index, element) in iter {
+            process_stanza_element(index, element, &mut title, &mut subtitle, &mut lines);
         }
 
         Stanza {
@@ -1813,6 +2087,7 @@ impl From<StanzaInternal> for Stanza {
 }
 
 fn process_stanza_element(
+    index: usize,
     element: StanzaChoice,
     title: &mut Option<Title>,
     subtitle: &mut Option<Paragraph>,
@@ -1820,6 +2095,7 @@ fn process_stanza_element(
 ) {
     match element {
         StanzaChoice::Stanza(s) => {
+            repair::unwrapped(index);
             if let Some(title) = s.title {
                 for element in title.elements {
                     match element {
@@ -1835,13 +2111,17 @@ fn process_stanza_element(
                 lines.push(line);
             }
         }
-        StanzaChoice::Paragraph(p) => lines.push(p),
+        StanzaChoice::Paragraph(p) => {
+            repair::converted(index, "v");
+            lines.push(p)
+        }
         // skip epigraphs because tricky to map to the stanza itself
-        StanzaChoice::Epigraph(_) => {}
+        StanzaChoice::Epigraph(_) => repair::dropped(index),
         StanzaChoice::Title(t) => {
             if lines.is_empty() && title.is_none() {
                 *title = Some(t);
             } else {
+                repair::unwrapped(index);
                 lines.extend(t.elements.into_iter().filter_map(|element| match element {
                     TitleElement::Paragraph(p) => Some(p),
                     TitleElement::EmptyLine => None,
@@ -1852,30 +2132,46 @@ fn process_stanza_element(
             if lines.is_empty() && subtitle.is_none() {
                 *subtitle = Some(s);
             } else {
+                repair::converted(index, "v");
                 lines.push(s);
             }
         }
         StanzaChoice::Line(l) => lines.push(l),
-        StanzaChoice::Link(l) => lines.push(Paragraph {
-            id: None,
-            lang: None,
-            style: None,
-            elements: vec![StyleElement::Link(l)],
-        }),
-        StanzaChoice::Image(i) => lines.push(Paragraph {
-            id: None,
-            lang: None,
-            style: None,
-            elements: vec![StyleElement::Image(i)],
-        }),
-        StanzaChoice::TextAuthor(p) => lines.push(p),
-        StanzaChoice::EmptyLine => {}
-        StanzaChoice::Text(t) => lines.push(Paragraph {
-            id: None,
-            lang: None,
-            style: None,
-            elements: vec![StyleElement::Text(t)],
-        }),
+        StanzaChoice::Link(l) => {
+            repair::wrapped(index, "v");
+            lines.push(Paragraph {
+                id: None,
+                lang: None,
+                style: None,
+                elements: vec![StyleElement::Link(l)],
+                extras: Extras::default(),
+            })
+        }
+        StanzaChoice::Image(i) => {
+            repair::wrapped(index, "v");
+            lines.push(Paragraph {
+                id: None,
+                lang: None,
+                style: None,
+                elements: vec![StyleElement::Image(i)],
+                extras: Extras::default(),
+            })
+        }
+        StanzaChoice::TextAuthor(p) => {
+            repair::converted(index, "v");
+            lines.push(p)
+        }
+        StanzaChoice::EmptyLine => repair::dropped(index),
+        StanzaChoice::Text(t) => {
+            repair::wrapped(index, "v");
+            lines.push(Paragraph {
+                id: None,
+                lang: None,
+                style: None,
+                elements: vec![StyleElement::Text(t)],
+                extras: Extras::default(),
+            })
+        }
     }
 }
 
@@ -1889,6 +2185,7 @@ pub struct Title {
     pub elements: Vec<TitleElement>,
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum TitleElement {
     #[serde(rename = "p")]
@@ -1929,24 +2226,38 @@ impl From<TitleInternal> for Title {
         }: TitleInternal,
     ) -> Self {
         let mut elements = Vec::with_capacity(choices.len());
-        for element in choices {
+        for (index, element) in choices.into_iter().enumerate() {
             match element {
                 TitleChoice::Paragraph(p) => elements.push(TitleElement::Paragraph(p)),
-                TitleChoice::Title(t) => elements.extend(t.elements),
-                TitleChoice::Subtitle(s) => elements.push(TitleElement::Paragraph(s)),
+                TitleChoice::Title(t) => {
+                    repair::unwrapped(index);
+                    elements.extend(t.elements)
+                }
+                TitleChoice::Subtitle(s) => {
+                    repair::converted(index, "p");
+                    elements.push(TitleElement::Paragraph(s))
+                }
                 TitleChoice::EmptyLine => elements.push(TitleElement::EmptyLine),
-                TitleChoice::Image(i) => elements.push(TitleElement::Paragraph(Paragraph {
-                    id: None,
-                    lang: None,
-                    style: None,
-                    elements: vec![StyleElement::Image(i)],
-                })),
-                TitleChoice::Text(t) => elements.push(TitleElement::Paragraph(Paragraph {
-                    id: None,
-                    lang: None,
-                    style: None,
-                    elements: vec![StyleElement::Text(t)],
-                })),
+                TitleChoice::Image(i) => {
+                    repair::wrapped(index, "p");
+                    elements.push(TitleElement::Paragraph(Paragraph {
+                        id: None,
+                        lang: None,
+                        style: None,
+                        elements: vec![StyleElement::Image(i)],
+                        extras: Extras::default(),
+                    }))
+                }
+                TitleChoice::Text(t) => {
+                    repair::wrapped(index, "p");
+                    elements.push(TitleElement::Paragraph(Paragraph {
+                        id: None,
+                        lang: None,
+                        style: None,
+                        elements: vec![StyleElement::Text(t)],
+                        extras: Extras::default(),
+                    }))
+                }
             }
         }
         Title { lang, elements }
@@ -1965,6 +2276,9 @@ pub struct Paragraph {
     pub style: Option<String>,
     #[serde(rename = "$value")]
     pub elements: Vec<StyleElement>,
+    /// Elements and attributes unknown to the model, e.g. of extensions, kept for serialization
+    #[serde(flatten, skip_serializing_if = "Extras::is_empty")]
+    pub extras: Extras,
 }
 
 #[derive(Deserialize)]
@@ -1994,6 +2308,7 @@ impl From<ParagraphInternal> for Paragraph {
             lang,
             style,
             elements,
+            extras: extras::take(Owner::Paragraph),
         }
     }
 }
@@ -2104,8 +2419,9 @@ impl From<TableCellInternal> for TableCell {
 }
 
 /// Align for table cells
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 pub enum HorizontalAlign {
+    #[default]
     #[serde(rename = "left")]
     Left,
     #[serde(rename = "right")]
@@ -2114,15 +2430,10 @@ pub enum HorizontalAlign {
     Center,
 }
 
-impl Default for HorizontalAlign {
-    fn default() -> Self {
-        HorizontalAlign::Left
-    }
-}
-
 /// Align for table cells
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 pub enum VerticalAlign {
+    #[default]
     #[serde(rename = "top")]
     Top,
     #[serde(rename = "middle")]
@@ -2131,12 +2442,6 @@ pub enum VerticalAlign {
     Bottom,
 }
 
-impl Default for VerticalAlign {
-    fn default() -> Self {
-        VerticalAlign::Top
-    }
-}
-
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(from = "NamedStyleInternal")]
 pub struct NamedStyle {
@@ -2532,18 +2837,23 @@ enum StyleChoice {
     #[serde(rename = "title")]
     Title(Title),
     // skipping because difficult to encode in a readable way
+    #[allow(dead_code)]
     #[serde(rename = "body")]
     Body(Body),
     // skipping because difficult to encode in a readable way
+    #[allow(dead_code)]
     #[serde(rename = "stanza")]
     Stanza(Stanza),
     // skipping because difficult to encode in a readable way
+    #[allow(dead_code)]
     #[serde(rename = "poem")]
     Poem(Poem),
     // skipping because difficult to encode in a readable way
+    #[allow(dead_code)]
     #[serde(rename = "cite")]
     Cite(Cite),
     // skipping because difficult to encode in a readable way
+    #[allow(dead_code)]
     #[serde(rename = "table")]
     Table(Table),
     #[serde(rename = "empty-line")]
@@ -2574,20 +2884,23 @@ enum StyleChoice {
 
 fn parse_style_elements_permissively(choices: Vec<StyleChoice>) -> Vec<StyleElement> {
     let mut elements = Vec::with_capacity(choices.len());
-    for element in choices {
+    for (index, element) in choices.into_iter().enumerate() {
         match element {
             StyleChoice::Paragraph(p) => {
                 if let Some(id) = p.id {
+                    repair::converted(index, "style");
                     elements.push(StyleElement::Style(NamedStyle {
                         name: id,
                         lang: p.lang,
                         elements: p.elements,
                     }));
                 } else {
+                    repair::unwrapped(index);
                     elements.extend(p.elements);
                 }
             }
             StyleChoice::StanzaLine(p) => {
+                repair::converted(index, "emphasis");
                 let element = StyleElement::Emphasis(Style {
                     lang: p.lang,
                     elements: p.elements,
@@ -2603,6 +2916,7 @@ fn parse_style_elements_permissively(choices: Vec<StyleChoice>) -> Vec<StyleElem
                 }
             }
             StyleChoice::TextAuthor(p) => {
+                repair::converted(index, "emphasis");
                 let element = StyleElement::Emphasis(Style {
                     lang: p.lang,
                     elements: p.elements,
@@ -2618,6 +2932,7 @@ fn parse_style_elements_permissively(choices: Vec<StyleChoice>) -> Vec<StyleElem
                 }
             }
             StyleChoice::Title(t) => {
+                repair::converted(index, "strong");
                 for element in t.elements {
                     match element {
                         TitleElement::Paragraph(p) => {
@@ -2641,23 +2956,26 @@ fn parse_style_elements_permissively(choices: Vec<StyleChoice>) -> Vec<StyleElem
             }
             // it's difficult to encode an arbitrary body
             // in a readable way, so skipping...
-            StyleChoice::Body(_) => {}
+            StyleChoice::Body(_) => repair::dropped(index),
             // it's difficult to encode an arbitrary poem
             // in a readable way, so skipping...
-            StyleChoice::Stanza(_) => {}
+            StyleChoice::Stanza(_) => repair::dropped(index),
             // it's difficult to encode an arbitrary poem
             // in a readable way, so skipping...
-            StyleChoice::Poem(_) => {}
+            StyleChoice::Poem(_) => repair::dropped(index),
             // it's difficult to encode an arbitrary cite
             // in a readable way, so skipping...
-            StyleChoice::Cite(_) => {}
+            StyleChoice::Cite(_) => repair::dropped(index),
             // it's difficult to encode table
             // in a readable way, so skipping...
-            StyleChoice::Table(_) => {}
-            StyleChoice::EmptyLine => {}
+            StyleChoice::Table(_) => repair::dropped(index),
+            StyleChoice::EmptyLine => repair::dropped(index),
             StyleChoice::Strong(s) => elements.push(StyleElement::Strong(s)),
             StyleChoice::Emphasis(e) => elements.push(StyleElement::Emphasis(e)),
-            StyleChoice::Italics(s) => elements.push(StyleElement::Emphasis(s)),
+            StyleChoice::Italics(s) => {
+                repair::converted(index, "emphasis");
+                elements.push(StyleElement::Emphasis(s))
+            }
             StyleChoice::Style(s) => elements.push(StyleElement::Style(s)),
             StyleChoice::Link(l) => elements.push(StyleElement::Link(l)),
             StyleChoice::Strikethrough(s) => elements.push(StyleElement::Strikethrough(s)),
@@ -3022,7 +3340,7 @@ pub struct Date {
     #[serde(rename = "@lang", skip_serializing_if = "Option::is_none")]
     pub lang: Option<LanguageTag>,
     #[serde(rename = "@value", skip_serializing_if = "Option::is_none")]
-    pub iso_date: Option<chrono::NaiveDate>,
+    pub iso_date: Option<IsoDate>,
     #[serde(rename = "$text")]
     pub display_date: Option<String>,
 }
@@ -3045,7 +3363,10 @@ impl From<DateInternal> for Date {
             display_date,
         }: DateInternal,
     ) -> Self {
-        let iso_date = iso_date.and_then(|raw_date| raw_date.parse().ok());
+        let iso_date = iso_date.map(IsoDate::from);
+        if iso_date.as_ref().is_some_and(|date| date.date().is_none()) {
+            repair::record(Subject::Attribute("value"), RepairAction::InvalidValue);
+        }
         Date {
             lang,
             iso_date,
@@ -3096,241 +3417,6 @@ pub struct LocalizedText {
     pub value: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
-#[serde(rename_all = "snake_case")]
-pub enum Genre {
-    Accounting,
-    AdvAnimal,
-    AdvGeo,
-    AdvHistory,
-    AdvIndian,
-    AdvMaritime,
-    AdvWestern,
-    Adventure,
-    Antique,
-    AntiqueAnt,
-    AntiqueEast,
-    AntiqueEuropean,
-    AntiqueMyths,
-    AntiqueRussian,
-    AphorismQuote,
-    Architecture,
-    ArchitectureBook,
-    AutoRegulations,
-    Banking,
-    BeginningAuthors,
-    Business,
-    ChildAdv,
-    ChildDet,
-    ChildEducation,
-    ChildProse,
-    ChildSf,
-    ChildTale,
-    ChildVerse,
-    Children,
-    CinemaTheatre,
-    CityFantasy,
-    CompDb,
-    CompHard,
-    CompOsnet,
-    CompProgramming,
-    CompSoft,
-    CompWww,
-    Computers,
-    Design,
-    DetAction,
-    DetClassic,
-    DetCrime,
-    DetEspionage,
-    DetHard,
-    DetHistory,
-    DetIrony,
-    DetManiac,
-    DetPolice,
-    DetPolitical,
-    Detective,
-    DragonFantasy,
-    Dramaturgy,
-    Economics,
-    EconomicsRef,
-    EntertHumor,
-    Essays,
-    FantasyFight,
-    ForeignAction,
-    ForeignAdventure,
-    ForeignAntique,
-    ForeignBusiness,
-    ForeignChildren,
-    ForeignComp,
-    ForeignContemporary,
-    ForeignContemporaryLit,
-    ForeignDesc,
-    ForeignDetective,
-    ForeignDramaturgy,
-    ForeignEdu,
-    ForeignFantasy,
-    ForeignHome,
-    ForeignHumor,
-    ForeignLanguage,
-    ForeignLove,
-    ForeignNovel,
-    ForeignOther,
-    ForeignPoetry,
-    ForeignProse,
-    ForeignPsychology,
-    ForeignPublicism,
-    ForeignReligion,
-    ForeignSf,
-    GeoGuides,
-    GeographyBook,
-    GlobalEconomy,
-    HealthPsy,
-    HistoricalFantasy,
-    Home,
-    HomeCooking,
-    HomeCrafts,
-    HomeDiy,
-    HomeEntertain,
-    HomeGarden,
-    HomeHealth,
-    HomePets,
-    HomeSex,
-    HomeSport,
-    HorrorFantasy,
-    HorrorVampires,
-    Humor,
-    HumorAnecdote,
-    HumorFantasy,
-    HumorProse,
-    HumorVerse,
-    Industries,
-    JobHunting,
-    Literature18,
-    Literature19,
-    Literature20,
-    LiteratureAdv,
-    LiteratureFairy,
-    LiteratureHistory,
-    Love,
-    LoveContemporary,
-    LoveDetective,
-    LoveErotica,
-    LoveFantasy,
-    LoveHistory,
-    LoveSf,
-    LoveShort,
-    MagicianBook,
-    Management,
-    Marketing,
-    MilitarySpecial,
-    MusicDancing,
-    Narrative,
-    Newspapers,
-    NonfBiography,
-    NonfCriticism,
-    NonfPublicism,
-    Nonfiction,
-    NonfictionPolitics,
-    NonfictionSociology,
-    OrgBehavior,
-    PaperWork,
-    PedagogyBook,
-    Periodic,
-    PersonalFinance,
-    Poetry,
-    Popadanec,
-    PopularBusiness,
-    Prose,
-    ProseClassic,
-    ProseContemporary,
-    ProseCounter,
-    ProseHistory,
-    ProseMilitary,
-    ProseRusClassic,
-    ProseSuClassics,
-    PsyAlassic,
-    PsyChilds,
-    PsyGeneric,
-    PsyPersonal,
-    PsySexAndFamily,
-    PsySocial,
-    PsyTheraphy,
-    RealEstate,
-    RefDict,
-    RefEncyc,
-    RefGuide,
-    RefRef,
-    Reference,
-    Religion,
-    ReligionBudda,
-    ReligionEsoterics,
-    ReligionRel,
-    ReligionSelf,
-    RomanceContemporary,
-    RomanceFantasy,
-    RomanceHistorical,
-    RomanceRomanticSuspense,
-    RomanceSf,
-    RussianContemporary,
-    RussianFantasy,
-    SciBiology,
-    SciBusiness,
-    SciChem,
-    SciCosmos,
-    SciCulture,
-    SciEconomy,
-    SciHistory,
-    SciJuris,
-    SciLinguistic,
-    SciMath,
-    SciMedicine,
-    SciPhilology,
-    SciPhilosophy,
-    SciPhys,
-    SciPolitics,
-    SciPsychology,
-    SciRadio,
-    SciReligion,
-    SciState,
-    SciTech,
-    SciTransport,
-    Science,
-    Sf,
-    SfAction,
-    SfCyberPunk,
-    SfCyberpunk,
-    SfDetective,
-    SfEpic,
-    SfFantasy,
-    SfFantasyCity,
-    SfHeroic,
-    SfHistory,
-    SfHorror,
-    SfHumor,
-    SfMystic,
-    SfPostapocalyptic,
-    SfSocial,
-    SfSpace,
-    SfWriting,
-    ShortStory,
-    Sketch,
-    SmallBusiness,
-    SociologyBook,
-    Stock,
-    Thriller,
-    ThrillerMystery,
-    Unrecognised,
-    UpbringingBook,
-    VampireBook,
-    VisualArts,
-}
-
-impl Default for Genre {
-    fn default() -> Self {
-        Genre::Unrecognised
-    }
-}
-
 fn should_prepend_whitespace(value: &str) -> bool {
     let first_char = value.chars().next().unwrap_or('\0');
     value.trim_start() == value
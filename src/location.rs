@@ -0,0 +1,468 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::rc::Rc;
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+/// Position of a node in a document
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Location {
+    /// Offset in bytes from the start of the document. A document in an encoding other than UTF-8
+    /// is decoded upfront by most functions, then the offset refers to the decoded text
+    pub offset: u64,
+    /// Starts from 1
+    pub line: usize,
+    /// Starts from 1, counted in characters
+    pub column: usize,
+    /// Path of the node, e.g. `/FictionBook/body/section[2]/p[3]`.
+    /// An element has an index if its parent has several elements with the same name
+    pub path: String,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{} {}", self.line, self.column, self.path)
+    }
+}
+
+/// A node that is looked up relatively to an element
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Subject {
+    /// The element itself
+    Element,
+    /// A child element or a text by its index among the children
+    Child(usize),
+    /// The first child element with a name
+    Named(&'static str),
+    /// An attribute of the element
+    Attribute(&'static str),
+    /// An unknown attribute or element by its offset, which is found regardless of the element
+    Unknown(u64),
+}
+
+/// A node to look up, the element is identified by the offset right after its end
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Target {
+    pub(crate) end: u64,
+    pub(crate) subject: Subject,
+}
+
+struct Node {
+    name: String,
+    /// Index among the siblings with the same name, starts from 1
+    ordinal: usize,
+    parent: Option<Rc<Node>>,
+    /// Amount of children with each name
+    children: RefCell<HashMap<String, usize>>,
+}
+
+struct Child {
+    name: String,
+    ordinal: usize,
+    start: u64,
+    /// Hidden from the deserializer, so it isn't counted among the children
+    hidden: bool,
+}
+
+struct Open {
+    node: Rc<Node>,
+    start: u64,
+    /// End of the start tag
+    tag_end: u64,
+    children: Vec<Child>,
+    /// Whether the last child is a text, which the deserializer joins with a text
+    /// that follows a comment
+    after_text: bool,
+}
+
+struct Found {
+    node: Rc<Node>,
+    attribute: Option<String>,
+    offset: u64,
+}
+
+/// Elements met so far while a document is scanned
+struct Tree<'a> {
+    stack: Vec<Open>,
+    /// The element that was closed by the last event
+    closed: Option<Open>,
+    /// Local names of the elements hidden by comments, by their offsets
+    hidden: &'a HashMap<u64, String>,
+}
+
+impl<'a> Tree<'a> {
+    fn new(hidden: &'a HashMap<u64, String>) -> Self {
+        Tree {
+            stack: vec![],
+            closed: None,
+            hidden,
+        }
+    }
+
+    /// Applies an event that starts at `start` and ends right before `position`
+    fn apply(&mut self, event: &Event, start: u64, position: u64) {
+        let (name, empty) = match event {
+            Event::Start(tag) => (tag.local_name().into_inner(), false),
+            Event::Empty(tag) => (tag.local_name().into_inner(), true),
+            Event::End(_) => {
+                self.closed = self.stack.pop();
+                return;
+            }
+            Event::Text(_) | Event::CData(_) => {
+                self.closed = None;
+                if let Some(parent) = self.stack.last_mut().filter(|parent| !parent.after_text) {
+                    let ordinal = count_child(&parent.node, "text()");
+                    parent.children.push(Child {
+                        name: "text()".to_string(),
+                        ordinal,
+                        start,
+                        hidden: false,
+                    });
+                    parent.after_text = true;
+                }
+                return;
+            }
+            Event::Comment(_) | Event::PI(_) => {
+                let hidden = self.hidden.get(&start);
+                if let (Some(name), Some(parent)) = (hidden, self.stack.last_mut()) {
+                    let ordinal = count_child(&parent.node, name);
+                    parent.children.push(Child {
+                        name: name.clone(),
+                        ordinal,
+                        start,
+                        hidden: true,
+                    });
+                }
+                return;
+            }
+            // skipped by the deserializer
+            _ => return,
+        };
+        let name = String::from_utf8_lossy(name).into_owned();
+        let parent = self.stack.last_mut();
+        let ordinal = match &parent {
+            Some(parent) => count_child(&parent.node, &name),
+            None => 1,
+        };
+        if let Some(parent) = parent {
+            parent.children.push(Child {
+                name: name.clone(),
+                ordinal,
+                start,
+                hidden: false,
+            });
+            parent.after_text = false;
+        }
+        let element = Open {
+            node: Rc::new(Node {
+                name,
+                ordinal,
+                parent: self.stack.last().map(|parent| parent.node.clone()),
+                children: RefCell::new(HashMap::new()),
+            }),
+            start,
+            tag_end: position,
+            children: vec![],
+            after_text: false,
+        };
+        if empty {
+            self.closed = Some(element);
+        } else {
+            self.closed = None;
+            self.stack.push(element);
+        }
+    }
+
+    /// The element the last event belongs to
+    fn current(&self) -> Option<&Open> {
+        self.closed.as_ref().or(self.stack.last())
+    }
+}
+
+/// Finds the locations of the targets by scanning the same text the document was parsed from,
+/// where the `hidden` elements are comments. Targets that don't match any element are not found
+pub(crate) fn locate(
+    source: &str,
+    hidden: &HashMap<u64, String>,
+    targets: &[Target],
+) -> Vec<Option<Location>> {
+    let mut order: Vec<usize> = (0..targets.len())
+        .filter(|&i| !matches!(targets[i].subject, Subject::Unknown(_)))
+        .collect();
+    order.sort_by_key(|&i| targets[i].end);
+    let mut unknown: Vec<(u64, usize)> = targets
+        .iter()
+        .enumerate()
+        .filter_map(|(i, target)| match target.subject {
+            Subject::Unknown(offset) => Some((offset, i)),
+            _ => None,
+        })
+        .collect();
+    unknown.sort();
+    let mut pending = order.into_iter().peekable();
+    let mut unknown = unknown.into_iter().peekable();
+    let mut found: Vec<Option<Found>> = targets.iter().map(|_| None).collect();
+
+    let mut reader = Reader::from_str(source);
+    reader.trim_text(true);
+    let mut tree = Tree::new(hidden);
+    while pending.peek().is_some() || unknown.peek().is_some() {
+        let before = reader.buffer_position();
+        let start = (before + leading_whitespace(&source[before..])) as u64;
+        let event = match reader.read_event() {
+            Ok(Event::Eof) => {
+                // nothing is left to read ahead after the root element
+                for i in pending.by_ref() {
+                    if let Some(element) = tree.current() {
+                        found[i] = resolve(source, element, targets[i].subject);
+                    }
+                }
+                break;
+            }
+            Err(_) => break,
+            Ok(event) => event,
+        };
+        let position = reader.buffer_position() as u64;
+        // the deserializer reads one event ahead, so an element is converted, and a target is
+        // recorded, when the event following the element is read
+        while let Some(&i) = pending.peek().filter(|&&i| targets[i].end <= position) {
+            if let Some(element) = tree.current() {
+                found[i] = resolve(source, element, targets[i].subject);
+            }
+            pending.next();
+        }
+        let tag = matches!(event, Event::Start(_) | Event::Empty(_));
+        tree.apply(&event, start, position);
+        // an unknown node is found by its offset, in the tag of an element or among the children
+        // of the open element
+        while let Some(&(_, i)) = unknown.peek().filter(|&&(offset, _)| offset < position) {
+            let element = if tag {
+                tree.current()
+            } else {
+                tree.stack.last()
+            };
+            if let Some(element) = element {
+                found[i] = resolve(source, element, targets[i].subject);
+            }
+            unknown.next();
+        }
+    }
+
+    let lines = line_starts(source);
+    found
+        .into_iter()
+        .map(|found| Some(location(source, &lines, found?)))
+        .collect()
+}
+
+/// Finds the place where a malformed document stops being parsable, i.e. the innermost element
+/// that is open at the first syntax error or at the unexpected end of the document
+pub(crate) fn locate_syntax_error(source: &str) -> Option<Location> {
+    let mut reader = Reader::from_str(source);
+    reader.trim_text(true);
+    let hidden = HashMap::new();
+    let mut tree = Tree::new(&hidden);
+    let offset = loop {
+        let before = reader.buffer_position();
+        let start = (before + leading_whitespace(&source[before..])) as u64;
+        match reader.read_event() {
+            Ok(Event::Eof) => break source.len() as u64,
+            Err(_) => break start,
+            Ok(event) => tree.apply(&event, start, reader.buffer_position() as u64),
+        }
+    };
+    let node = tree.stack.last()?.node.clone();
+    let found = Found {
+        node,
+        attribute: None,
+        offset,
+    };
+    Some(location(source, &line_starts(source), found))
+}
+
+fn location(source: &str, lines: &[usize], found: Found) -> Location {
+    let mut path = path(&found.node);
+    if let Some(attribute) = &found.attribute {
+        path.push_str("/@");
+        path.push_str(attribute);
+    }
+    let (line, column) = line_column(source, lines, found.offset);
+    Location {
+        offset: found.offset,
+        line,
+        column,
+        path,
+    }
+}
+
+fn resolve(source: &str, element: &Open, subject: Subject) -> Option<Found> {
+    let child = |child: &Child| Found {
+        node: Rc::new(Node {
+            name: child.name.clone(),
+            ordinal: child.ordinal,
+            parent: Some(element.node.clone()),
+            children: RefCell::new(HashMap::new()),
+        }),
+        attribute: None,
+        offset: child.start,
+    };
+    match subject {
+        Subject::Element => Some(Found {
+            node: element.node.clone(),
+            attribute: None,
+            offset: element.start,
+        }),
+        Subject::Child(index) => element
+            .children
+            .iter()
+            .filter(|child| !child.hidden)
+            .nth(index)
+            .map(child),
+        Subject::Named(name) => element
+            .children
+            .iter()
+            .find(|child| !child.hidden && child.name == name)
+            .map(child),
+        Subject::Attribute(name) => {
+            let tag = &source[element.start as usize..element.tag_end as usize];
+            let offset = attribute_position(tag, name).unwrap_or(0) as u64;
+            Some(Found {
+                node: element.node.clone(),
+                attribute: Some(name.to_string()),
+                offset: element.start + offset,
+            })
+        }
+        Subject::Unknown(offset) if offset == element.start => {
+            resolve(source, element, Subject::Element)
+        }
+        Subject::Unknown(offset) if offset < element.tag_end => {
+            let name = source[offset as usize..]
+                .split(|c: char| c == '=' || c.is_whitespace())
+                .next()
+                .unwrap_or_default();
+            Some(Found {
+                node: element.node.clone(),
+                attribute: Some(name.to_string()),
+                offset,
+            })
+        }
+        // an element too short for a comment is hidden by spaces, then the parent is found
+        Subject::Unknown(offset) => element
+            .children
+            .iter()
+            .find(|child| child.start == offset)
+            .map(child)
+            .or_else(|| resolve(source, element, Subject::Element)),
+    }
+}
+
+fn count_child(parent: &Node, name: &str) -> usize {
+    let mut children = parent.children.borrow_mut();
+    let count = children.entry(name.to_string()).or_default();
+    *count += 1;
+    *count
+}
+
+fn path(node: &Node) -> String {
+    let mut segments = vec![];
+    let mut current = Some(node);
+    while let Some(node) = current {
+        let siblings = node.parent.as_ref().map_or(1, |parent| {
+            parent
+                .children
+                .borrow()
+                .get(&node.name)
+                .copied()
+                .unwrap_or(1)
+        });
+        if siblings > 1 {
+            segments.push(format!("{}[{}]", node.name, node.ordinal));
+        } else {
+            segments.push(node.name.clone());
+        }
+        current = node.parent.as_deref();
+    }
+    segments.reverse();
+    format!("/{}", segments.join("/"))
+}
+
+/// Finds an attribute in a start tag, e.g. `<sequence name="x" number="y">`
+fn attribute_position(tag: &str, name: &str) -> Option<usize> {
+    let mut from = 0;
+    while let Some(found) = tag[from..].find(name) {
+        let position = from + found;
+        let before = tag[..position].chars().next_back();
+        let after = tag[position + name.len()..].trim_start();
+        if matches!(before, Some(c) if c.is_whitespace() || c == ':') && after.starts_with('=') {
+            let prefix = tag[..position]
+                .rfind(char::is_whitespace)
+                .map_or(0, |i| i + 1);
+            return Some(prefix);
+        }
+        from = position + name.len();
+    }
+    None
+}
+
+/// Spans of the attribute names of a start tag in their order, e.g. `name` and `number` in
+/// `<sequence name="x" number="y">`. The values are skipped, so a name inside a value isn't found
+pub(crate) fn attribute_names(tag: &str) -> Vec<Range<usize>> {
+    let bytes = tag.as_bytes();
+    let is_space = |b: u8| b.is_ascii_whitespace();
+    let name_end = |from: usize, stop: &dyn Fn(u8) -> bool| {
+        bytes[from..]
+            .iter()
+            .position(|&b| stop(b))
+            .map_or(bytes.len(), |i| from + i)
+    };
+    let skip_spaces = |from: usize| name_end(from, &|b| !is_space(b));
+    let mut names = vec![];
+    // the element name
+    let mut i = name_end(1, &|b| is_space(b) || b == b'/' || b == b'>');
+    loop {
+        i = skip_spaces(i);
+        if i >= bytes.len() || matches!(bytes[i], b'/' | b'>' | b'?') {
+            return names;
+        }
+        let end = name_end(i, &|b| is_space(b) || matches!(b, b'=' | b'/' | b'>'));
+        names.push(i..end);
+        i = skip_spaces(end);
+        if bytes.get(i) != Some(&b'=') {
+            continue;
+        }
+        i = skip_spaces(i + 1);
+        i = match bytes.get(i) {
+            Some(&quote @ (b'"' | b'\'')) => name_end(i + 1, &|b| b == quote) + 1,
+            _ => name_end(i, &|b| is_space(b) || b == b'>'),
+        };
+    }
+}
+
+fn leading_whitespace(text: &str) -> usize {
+    text.len() - text.trim_start_matches([' ', '\t', '\r', '\n']).len()
+}
+
+fn line_starts(source: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    starts.extend(
+        source
+            .bytes()
+            .enumerate()
+            .filter(|&(_, b)| b == b'\n')
+            .map(|(i, _)| i + 1),
+    );
+    starts
+}
+
+fn line_column(source: &str, lines: &[usize], offset: u64) -> (usize, usize) {
+    let offset = (offset as usize).min(source.len());
+    let line = lines.partition_point(|&start| start <= offset);
+    let start = lines[line - 1];
+    let column = source
+        .get(start..offset)
+        .map_or(offset - start, |text| text.chars().count());
+    (line, column + 1)
+}
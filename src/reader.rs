@@ -0,0 +1,176 @@
+use std::io::BufRead;
+
+use crate::scan::{Node, Scanner, Tag};
+use crate::{
+    process_body_element, push_body_sections, Binary, Body, BodyChoice, Description, Error,
+    Section, Stylesheet,
+};
+
+/// A part of a book yielded by [`BookReader`]
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum BookItem {
+    Stylesheet(Stylesheet),
+    Description(Description),
+    /// Start of a body. Its sections follow as separate items, so `sections` is always empty
+    Body(Body),
+    /// A top-level section of the last body
+    Section(Section),
+    Binary(Binary),
+}
+
+/// Reads a book part by part, so only one top-level section is kept in memory at a time.
+/// Items come in the document order: stylesheets and the description, then every body followed by
+/// its sections, then binaries. Sections are normalized the same way as in a [`FictionBook`](crate::FictionBook)
+pub struct BookReader<R> {
+    scanner: Scanner<R>,
+    in_body: bool,
+    /// Header of the current body until it is yielded
+    header: Option<Body>,
+    /// Amount of elements of the current body processed so far
+    index: usize,
+    /// Sections that are produced by a single element, e.g. a nested body
+    pending: Vec<Section>,
+    has_description: bool,
+    has_body: bool,
+    finished: bool,
+}
+
+impl<R: BufRead> BookReader<R> {
+    pub fn new(reader: R) -> Result<Self, Error> {
+        Ok(BookReader {
+            scanner: Scanner::new(reader)?,
+            in_body: false,
+            header: None,
+            index: 0,
+            pending: vec![],
+            has_description: false,
+            has_body: false,
+            finished: false,
+        })
+    }
+
+    fn read_item(&mut self) -> Result<Option<BookItem>, Error> {
+        if let Some(section) = self.pending.pop() {
+            return Ok(Some(BookItem::Section(section)));
+        }
+        loop {
+            match self.scanner.next()? {
+                Node::Start(tag) if tag.depth == 2 => {
+                    if let Some(item) = self.read_top_level(&tag)? {
+                        return Ok(Some(item));
+                    }
+                }
+                Node::Start(tag) if tag.depth == 3 && self.in_body => {
+                    let element: BodyChoice = self.scanner.deserialize(&tag)?;
+                    if let Some(item) = self.process_body_element(element) {
+                        return Ok(Some(item));
+                    }
+                }
+                Node::Text(text) if self.scanner.depth() == 2 && self.in_body => {
+                    if let Some(item) = self.process_body_element(BodyChoice::Text(text)) {
+                        return Ok(Some(item));
+                    }
+                }
+                Node::End if self.scanner.depth() == 1 => {
+                    // a body without sections still has to be reported
+                    self.in_body = false;
+                    if let Some(header) = self.header.take() {
+                        return Ok(Some(BookItem::Body(header)));
+                    }
+                }
+                Node::Start(_) | Node::End | Node::Text(_) => {}
+                Node::Eof => {
+                    let missing = if !self.has_description {
+                        "description"
+                    } else if !self.has_body {
+                        "body"
+                    } else {
+                        return Ok(None);
+                    };
+                    let error = Error::structure(format!("missing field `{missing}`"));
+                    return Err(error.at(self.scanner.root_location()));
+                }
+            }
+        }
+    }
+
+    fn read_top_level(&mut self, tag: &Tag) -> Result<Option<BookItem>, Error> {
+        let item = if tag.is("stylesheet") {
+            BookItem::Stylesheet(self.scanner.deserialize(tag)?)
+        } else if tag.is("description") {
+            self.has_description = true;
+            BookItem::Description(self.scanner.deserialize(tag)?)
+        } else if tag.is("body") {
+            self.has_body = true;
+            let lang = match self.scanner.attribute(tag, "lang")? {
+                Some(lang) => Some(lang.parse().map_err(|e| {
+                    let location = self.scanner.location(tag, Some("lang"));
+                    Error::structure(format!("{e}")).at(location)
+                })?),
+                None => None,
+            };
+            let header = Body {
+                name: self.scanner.attribute(tag, "name")?,
+                lang,
+                image: None,
+                title: None,
+                epigraphs: vec![],
+                sections: vec![],
+            };
+            self.in_body = true;
+            self.header = Some(header);
+            self.index = 0;
+            return Ok(None);
+        } else if tag.is("binary") {
+            BookItem::Binary(self.scanner.deserialize(tag)?)
+        } else {
+            self.scanner.skip(tag)?;
+            return Ok(None);
+        };
+        Ok(Some(item))
+    }
+
+    /// Returns the body header once the first section is produced, the sections are queued
+    fn process_body_element(&mut self, element: BodyChoice) -> Option<BookItem> {
+        let index = self.index;
+        self.index += 1;
+        let mut sections = vec![];
+        match &mut self.header {
+            Some(header) => process_body_element(
+                index,
+                element,
+                &mut header.image,
+                &mut header.title,
+                &mut header.epigraphs,
+                &mut sections,
+            ),
+            // the header is over once a section is produced
+            None => push_body_sections(index, element, &mut sections),
+        }
+        sections.reverse();
+        self.pending = sections;
+        if self.pending.is_empty() {
+            return None;
+        }
+        match self.header.take() {
+            Some(header) => Some(BookItem::Body(header)),
+            None => self.pending.pop().map(BookItem::Section),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for BookReader<R> {
+    type Item = Result<BookItem, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let item = self.read_item().transpose();
+        if !matches!(item, Some(Ok(_))) {
+            self.finished = true;
+        }
+        item
+    }
+}
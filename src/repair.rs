@@ -0,0 +1,256 @@
+//! Reporting of the fixes applied to documents that don't conform to the FB2 schema,
+//! and of the place where a document fails to deserialize.
+//!
+//! Conversions of the permissive internal types record repairs while a document is deserialized.
+//! A repair is recorded together with the amount of bytes consumed so far, which is the end of
+//! the element being converted, and the locations are resolved by scanning the text afterwards.
+//! An error is located the same way by the amount of bytes consumed before it.
+
+use std::cell::{Cell, RefCell};
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, Read};
+use std::rc::Rc;
+
+use quick_xml::DeError;
+use serde::de::DeserializeOwned;
+
+use crate::extras::{self, Scanned};
+use crate::location::{locate, locate_syntax_error, Location, Subject, Target};
+use crate::Error;
+
+/// A fix applied to a document that doesn't conform to the FB2 schema
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Repair {
+    /// Name of the repaired element, `text()` for text and `@name` for an attribute
+    pub element: String,
+    pub action: RepairAction,
+    pub location: Location,
+}
+
+/// What was done to a node that is not allowed in its place
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RepairAction {
+    /// The node was wrapped into a new element, e.g. `<strong>` directly in a section into `<p>`
+    Wrapped { into: &'static str },
+    /// The element was replaced by a similar one, e.g. `<text-author>` in a section by `<p>`
+    Converted { into: &'static str },
+    /// The element was removed and its content was moved into the parent,
+    /// e.g. sections of a `<body>` nested in a body
+    Unwrapped,
+    /// The node was dropped together with its content, e.g. `<poem>` in a paragraph
+    Dropped,
+    /// The value cannot be parsed, so it was ignored
+    InvalidValue,
+    /// The element or attribute is unknown to the schema, so it was kept as is
+    /// in the [`Extras`](crate::Extras) of its parent
+    Kept,
+}
+
+impl Display for Repair {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.location)?;
+        match self.action {
+            RepairAction::Wrapped { into } => write!(f, "`{}` wrapped into `{into}`", self.element),
+            RepairAction::Converted { into } => {
+                write!(f, "`{}` converted to `{into}`", self.element)
+            }
+            RepairAction::Unwrapped => write!(f, "`{}` unwrapped into its parent", self.element),
+            RepairAction::Dropped => write!(f, "`{}` dropped", self.element),
+            RepairAction::InvalidValue => write!(f, "invalid value of `{}` ignored", self.element),
+            RepairAction::Kept => write!(f, "unknown `{}` kept", self.element),
+        }
+    }
+}
+
+struct Recorder {
+    position: Rc<Cell<u64>>,
+    /// Repairs are not kept unless they are reported
+    records: Option<Vec<(Target, RepairAction)>>,
+}
+
+thread_local! {
+    static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+/// Records a repair of a node relative to the element being converted, does nothing unless
+/// the repairs are collected
+pub(crate) fn record(subject: Subject, action: RepairAction) {
+    RECORDER.with(|recorder| {
+        if let Some(recorder) = recorder.borrow_mut().as_mut() {
+            let target = Target {
+                end: recorder.position.get(),
+                subject,
+            };
+            if let Some(records) = &mut recorder.records {
+                records.push((target, action));
+            }
+        }
+    })
+}
+
+pub(crate) fn wrapped(index: usize, into: &'static str) {
+    record(Subject::Child(index), RepairAction::Wrapped { into });
+}
+
+pub(crate) fn converted(index: usize, into: &'static str) {
+    record(Subject::Child(index), RepairAction::Converted { into });
+}
+
+pub(crate) fn unwrapped(index: usize) {
+    record(Subject::Child(index), RepairAction::Unwrapped);
+}
+
+pub(crate) fn dropped(index: usize) {
+    record(Subject::Child(index), RepairAction::Dropped);
+}
+
+pub(crate) fn kept(offset: u64) {
+    record(Subject::Unknown(offset), RepairAction::Kept);
+}
+
+/// Deserializes the text and collects the repairs applied meanwhile
+pub(crate) fn collect<T: DeserializeOwned>(source: &str) -> Result<(T, Vec<Repair>), Error> {
+    deserialize(source, true, false)
+}
+
+/// Deserializes the text, and locates the error if it fails
+pub(crate) fn parse<T: DeserializeOwned>(source: &str) -> Result<T, Error> {
+    let (value, _) = deserialize(source, false, false)?;
+    Ok(value)
+}
+
+/// Deserializes the text keeping the comments and processing instructions,
+/// and locates the error if it fails
+pub(crate) fn parse_with_misc<T: DeserializeOwned>(source: &str) -> Result<T, Error> {
+    let (value, _) = deserialize(source, false, true)?;
+    Ok(value)
+}
+
+fn deserialize<T: DeserializeOwned>(
+    source: &str,
+    report: bool,
+    misc: bool,
+) -> Result<(T, Vec<Repair>), Error> {
+    // quick-xml skips the byte order mark without counting it
+    let (source, origin) = match source.strip_prefix('\u{feff}') {
+        Some(source) => (source, '\u{feff}'.len_utf8() as u64),
+        None => (source, 0),
+    };
+    // the hidden unknown elements keep their length, so the offsets refer to the source,
+    // but the children are located in the text the deserializer reads
+    let Scanned {
+        text,
+        collected,
+        hidden,
+    } = extras::collect(source, misc);
+    let position = Rc::new(Cell::new(0));
+    let _extras = extras::Session::start(collected, position.clone());
+    let session = Session::start(position.clone(), report);
+    let result: Result<T, DeError> = quick_xml::de::from_reader(Counter {
+        inner: text.as_bytes(),
+        position: position.clone(),
+    });
+    let recorder = session.finish();
+    let value = match result {
+        Ok(value) => value,
+        Err(e) => {
+            let located = matches!(e, DeError::InvalidXml(_) | DeError::UnexpectedEof);
+            let location = if located {
+                locate_syntax_error(source)
+            } else {
+                let target = Target {
+                    end: position.get(),
+                    subject: Subject::Element,
+                };
+                locate(&text, &hidden, &[target]).pop().flatten()
+            };
+            let location = location.map(|mut location| {
+                location.offset += origin;
+                location
+            });
+            return Err(Error::from(e).at(location));
+        }
+    };
+
+    let records = recorder.records.unwrap_or_default();
+    let targets: Vec<_> = records.iter().map(|(target, _)| *target).collect();
+    let locations = locate(&text, &hidden, &targets);
+    let mut repairs: Vec<_> = records
+        .into_iter()
+        .zip(locations)
+        .filter_map(|((target, action), location)| {
+            let mut location = location?;
+            location.offset += origin;
+            let element = match target.subject {
+                Subject::Attribute(name) => format!("@{name}"),
+                _ => element_name(&location.path),
+            };
+            Some(Repair {
+                element,
+                action,
+                location,
+            })
+        })
+        .collect();
+    repairs.sort_by_key(|repair| repair.location.offset);
+    Ok((value, repairs))
+}
+
+/// Enables recording for the current thread, and restores the previous state when dropped,
+/// even if the deserialization panics
+struct Session {
+    previous: Option<Recorder>,
+}
+
+impl Session {
+    fn start(position: Rc<Cell<u64>>, report: bool) -> Self {
+        let recorder = Recorder {
+            position,
+            records: report.then(Vec::new),
+        };
+        let previous = RECORDER.with(|r| r.replace(Some(recorder)));
+        Session { previous }
+    }
+
+    fn finish(self) -> Recorder {
+        let recorder = RECORDER.with(|r| r.borrow_mut().take());
+        recorder.expect("the recorder is set while the session lasts")
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        RECORDER.with(|r| r.replace(previous));
+    }
+}
+
+fn element_name(path: &str) -> String {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.split('[').next().unwrap_or(name).to_string()
+}
+
+/// Counts the bytes consumed from a reader
+struct Counter<R> {
+    inner: R,
+    position: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for Counter<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.position.set(self.position.get() + read as u64);
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for Counter<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position.set(self.position.get() + amount as u64);
+        self.inner.consume(amount)
+    }
+}
@@ -0,0 +1,647 @@
+//! Low level traversal of a document that doesn't deserialize elements unless asked to.
+//! It enables reading the parts of a book without paying for the rest of it.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+use std::ops::Range;
+
+#[cfg(feature = "encoding")]
+use encoding_rs::{Encoding, UTF_8};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::location::locate_syntax_error;
+use crate::{Error, Location};
+
+const UTF_8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Enough to contain the XML declaration and a good sample of text for the encoding detection
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// A node of a document met during the traversal
+pub(crate) enum Node {
+    Start(Tag),
+    End,
+    /// Trimmed and unescaped text
+    Text(String),
+    Eof,
+}
+
+/// A start tag of an element, empty elements are reported as a start tag immediately
+/// followed by the end tag
+pub(crate) struct Tag {
+    start: BytesStart<'static>,
+    empty: bool,
+    /// 1 for the root element
+    pub(crate) depth: usize,
+    /// Position of the `<` of the tag
+    mark: Mark,
+}
+
+/// A position in the source
+#[derive(Clone, Copy, Default)]
+struct Mark {
+    offset: u64,
+    /// Starts from 1
+    line: usize,
+    /// Starts from 1, counted in characters
+    column: usize,
+}
+
+/// An open element
+struct Entry {
+    name: String,
+    /// Index among the siblings with the same name met so far, starts from 1
+    ordinal: usize,
+    /// Amount of children with each name met so far
+    children: HashMap<String, usize>,
+    mark: Mark,
+}
+
+impl Tag {
+    /// Local name of the element, i.e. without a namespace prefix
+    pub(crate) fn name(&self) -> &[u8] {
+        self.start.local_name().into_inner()
+    }
+
+    pub(crate) fn is(&self, name: &str) -> bool {
+        self.name() == name.as_bytes()
+    }
+}
+
+pub(crate) struct Scanner<R> {
+    reader: Reader<Recorder<BufReader<Source<R>>>>,
+    buffer: Vec<u8>,
+    /// Open elements, starting from the root
+    stack: Vec<Entry>,
+    /// The root element once it is closed, to locate errors found at the end of the document
+    root: Option<Entry>,
+    /// Set when an empty element was reported as a start tag
+    pending_end: bool,
+    decoder: Decoder,
+    /// Amount of bytes consumed before the reader was created, i.e. a byte order mark
+    origin: u64,
+}
+
+impl<R: BufRead> Scanner<R> {
+    pub(crate) fn new(reader: R) -> Result<Self, Error> {
+        let (mut reader, decoder) = Decoder::detect(reader)?;
+        // quick-xml skips the byte order mark without counting it in the position
+        let origin = if reader.fill_buf()?.starts_with(UTF_8_BOM) {
+            reader.consume(UTF_8_BOM.len());
+            UTF_8_BOM.len() as u64
+        } else {
+            0
+        };
+        // whitespace is trimmed by the scanner, so a tag starts right after the previous event
+        let reader = Reader::from_reader(Recorder {
+            inner: reader,
+            recording: None,
+            counter: Counter {
+                single_byte: decoder.is_single_byte(),
+                line: 1,
+                column: 1,
+                consumed: 0,
+            },
+        });
+        Ok(Scanner {
+            reader,
+            buffer: vec![],
+            stack: vec![],
+            root: None,
+            pending_end: false,
+            decoder,
+            origin,
+        })
+    }
+
+    /// Depth of the current element, 0 outside the root element
+    pub(crate) fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Offset of the next unread byte in the source
+    pub(crate) fn position(&self) -> u64 {
+        self.origin + self.reader.buffer_position() as u64
+    }
+
+    fn mark(&self) -> Mark {
+        let counter = &self.reader.get_ref().counter;
+        let position = self.reader.buffer_position() as u64;
+        // the `<` that ends a text is consumed, but it is not counted in the position yet
+        let ahead = counter.consumed - position;
+        Mark {
+            offset: self.origin + position,
+            line: counter.line,
+            column: counter.column - ahead as usize,
+        }
+    }
+
+    /// Whether offsets refer to the text decoded upfront instead of the source bytes
+    pub(crate) fn is_transcoded(&self) -> bool {
+        match self.reader.get_ref().inner.get_ref() {
+            Source::Stream(_) => false,
+            #[cfg(feature = "encoding")]
+            Source::Decoded(_) => true,
+        }
+    }
+
+    pub(crate) fn next(&mut self) -> Result<Node, Error> {
+        if self.pending_end {
+            self.pending_end = false;
+            self.leave();
+            return Ok(Node::End);
+        }
+        loop {
+            self.buffer.clear();
+            let mark = self.mark();
+            let event = match self.reader.read_event_into(&mut self.buffer) {
+                Ok(event) => event,
+                Err(e) => return Err(Error::from(e).at(locate(&self.stack, None, mark))),
+            };
+            match event {
+                Event::Start(start) => {
+                    let start = start.into_owned();
+                    return Ok(Node::Start(self.enter(start, false, mark)));
+                }
+                Event::Empty(start) => {
+                    let start = start.into_owned();
+                    self.pending_end = true;
+                    return Ok(Node::Start(self.enter(start, true, mark)));
+                }
+                Event::End(_) => {
+                    self.leave();
+                    return Ok(Node::End);
+                }
+                Event::Text(text) => {
+                    let text = self.decoder.decode(&text)?;
+                    let text = quick_xml::escape::unescape(&text)
+                        .map_err(quick_xml::Error::EscapeError)?;
+                    let text = text.trim_matches(is_whitespace);
+                    if !text.is_empty() {
+                        return Ok(Node::Text(text.to_string()));
+                    }
+                }
+                Event::CData(text) => return Ok(Node::Text(self.decoder.decode(&text)?)),
+                Event::Eof => match self.stack.last() {
+                    Some(open) => {
+                        let error = quick_xml::Error::UnexpectedEof(open.name.clone());
+                        let location = locate(&self.stack, None, self.mark());
+                        return Err(Error::syntax(error).at(location));
+                    }
+                    None => return Ok(Node::Eof),
+                },
+                Event::Decl(_) | Event::PI(_) | Event::DocType(_) | Event::Comment(_) => {}
+            }
+        }
+    }
+
+    fn enter(&mut self, start: BytesStart<'static>, empty: bool, mark: Mark) -> Tag {
+        let name = String::from_utf8_lossy(start.local_name().into_inner()).into_owned();
+        let ordinal = match self.stack.last_mut() {
+            Some(parent) => {
+                let count = parent.children.entry(name.clone()).or_default();
+                *count += 1;
+                *count
+            }
+            None => 1,
+        };
+        self.stack.push(Entry {
+            name,
+            ordinal,
+            children: HashMap::new(),
+            mark,
+        });
+        Tag {
+            start,
+            empty,
+            depth: self.stack.len(),
+            mark,
+        }
+    }
+
+    fn leave(&mut self) {
+        let entry = self.stack.pop();
+        if self.stack.is_empty() {
+            self.root = entry;
+        }
+    }
+
+    /// Location of an element whose start tag was just returned by [`Scanner::next`],
+    /// or of its attribute. Siblings that follow the element are not known yet,
+    /// so the first of several elements with the same name has no index in the path
+    pub(crate) fn location(&self, tag: &Tag, attribute: Option<&str>) -> Option<Location> {
+        let mut location = locate(&self.stack, attribute, tag.mark)?;
+        location.offset = tag.mark.offset;
+        Some(location)
+    }
+
+    /// Location of the root element, e.g. for an error about a missing element
+    pub(crate) fn root_location(&self) -> Option<Location> {
+        let root = self.stack.first().or(self.root.as_ref())?;
+        locate(std::slice::from_ref(root), None, root.mark)
+    }
+
+    /// Deserializes the element whose start tag was just returned by [`Scanner::next`],
+    /// an error is located in the source rather than in the element
+    pub(crate) fn deserialize<T: serde::de::DeserializeOwned>(
+        &mut self,
+        tag: &Tag,
+    ) -> Result<T, Error> {
+        let path = self.path();
+        let fragment = self.capture(tag)?;
+        crate::repair::parse(&fragment).map_err(|mut error| {
+            if let Some(Some(location)) = error.location_mut() {
+                self.relocate(location, tag, path.as_deref(), &fragment);
+            }
+            error
+        })
+    }
+
+    /// Path of the innermost open element
+    fn path(&self) -> Option<String> {
+        locate(&self.stack, None, Mark::default()).map(|location| location.path)
+    }
+
+    /// Translates a location in a fragment captured from the tag to the source
+    fn relocate(&self, location: &mut Location, tag: &Tag, path: Option<&str>, fragment: &str) {
+        self.shift(location, tag.mark, fragment);
+        if let (Some(path), Some(rest)) = (path, location.path.get(1..)) {
+            let rest = rest.find('/').map_or("", |i| &rest[i..]);
+            location.path = format!("{path}{rest}");
+        }
+    }
+
+    /// Translates a location in a fragment that starts at the mark to the source
+    fn shift(&self, location: &mut Location, mark: Mark, fragment: &str) {
+        let before = fragment.get(..location.offset as usize).unwrap_or(fragment);
+        location.offset = mark.offset + self.decoder.encoded_len(before);
+        if location.line == 1 {
+            location.column += mark.column - 1;
+        }
+        location.line += mark.line - 1;
+    }
+
+    /// Reads the rest of the element whose start tag was just returned by [`Scanner::next`],
+    /// and returns the whole element as a standalone XML fragment
+    pub(crate) fn capture(&mut self, tag: &Tag) -> Result<String, Error> {
+        let mut fragment = vec![b'<'];
+        fragment.extend_from_slice(&tag.start);
+        if tag.empty {
+            self.pending_end = false;
+            self.leave();
+            fragment.extend_from_slice(b"/>");
+            return self.decoder.decode(&fragment);
+        }
+        fragment.push(b'>');
+        self.reader.get_mut().recording = Some(fragment);
+        let result = self.read_to_end(tag);
+        let fragment = self.reader.get_mut().recording.take().unwrap_or_default();
+        if let Err((error, mark)) = result {
+            // the fragment read so far tells which nested element is malformed
+            let read = (mark.offset - tag.mark.offset) as usize;
+            let text = self.decoder.decode(&fragment[..read.min(fragment.len())])?;
+            let location = locate_syntax_error(&text).map(|mut location| {
+                self.relocate(&mut location, tag, self.path().as_deref(), &text);
+                location
+            });
+            let location = location.or_else(|| locate(&self.stack, None, mark));
+            return Err(Error::from(error).at(location));
+        }
+        self.leave();
+        self.decoder.decode(&fragment)
+    }
+
+    /// Appends the start tag of an element to a document, e.g. to wrap captured children
+    pub(crate) fn open(&self, tag: &Tag, document: &mut Assembly) -> Result<(), Error> {
+        let mut fragment = vec![b'<'];
+        fragment.extend_from_slice(&tag.start);
+        fragment.push(b'>');
+        document.push(&self.decoder.decode(&fragment)?, tag.mark);
+        Ok(())
+    }
+
+    /// Appends the end tag of an element to a document, see [`Scanner::open`]
+    pub(crate) fn close(&self, tag: &Tag, document: &mut Assembly) -> Result<(), Error> {
+        let mut fragment = b"</".to_vec();
+        fragment.extend_from_slice(tag.start.name().into_inner());
+        fragment.push(b'>');
+        document.push(&self.decoder.decode(&fragment)?, self.mark());
+        Ok(())
+    }
+
+    /// Reads the element whose start tag was just returned by [`Scanner::next`],
+    /// and appends it to a document
+    pub(crate) fn append(&mut self, tag: &Tag, document: &mut Assembly) -> Result<(), Error> {
+        let fragment = self.capture(tag)?;
+        document.push(&fragment, tag.mark);
+        Ok(())
+    }
+
+    /// Deserializes an assembled document, an error is located in the source rather than
+    /// in the document
+    pub(crate) fn parse<T: serde::de::DeserializeOwned>(
+        &self,
+        document: &Assembly,
+    ) -> Result<T, Error> {
+        crate::repair::parse(&document.text).map_err(|mut error| {
+            if let Some(Some(location)) = error.location_mut() {
+                let part = document
+                    .parts
+                    .partition_point(|&(start, _)| start as u64 <= location.offset);
+                if let Some(&(start, mark)) = document.parts.get(part.wrapping_sub(1)) {
+                    let fragment = &document.text[start..];
+                    let before = &document.text[..start];
+                    let line = before.matches('\n').count();
+                    if location.line == line + 1 {
+                        let last = before.rsplit('\n').next().unwrap_or(before);
+                        location.column -= last.chars().count();
+                    }
+                    location.line -= line;
+                    location.offset -= start as u64;
+                    self.shift(location, mark, fragment);
+                }
+            }
+            error
+        })
+    }
+
+    /// Reads the rest of the element whose start tag was just returned by [`Scanner::next`],
+    /// and returns the range of its content in the source, i.e. everything between the tags
+    pub(crate) fn content_range(&mut self, tag: &Tag) -> Result<Range<u64>, Error> {
+        let start = self.position();
+        if tag.empty {
+            self.skip(tag)?;
+            return Ok(start..start);
+        }
+        let mut end = start;
+        let mut nested = 0;
+        loop {
+            self.buffer.clear();
+            let mark = self.mark();
+            let event = match self.reader.read_event_into(&mut self.buffer) {
+                Ok(event) => event,
+                Err(e) => return Err(Error::from(e).at(locate(&self.stack, None, mark))),
+            };
+            match event {
+                Event::Text(text) => {
+                    let trailing = text.len() - text.trim_ascii_end().len();
+                    if trailing < text.len() {
+                        end = self.position() - trailing as u64;
+                    }
+                }
+                Event::Start(_) => nested += 1,
+                Event::End(_) if nested == 0 => break,
+                Event::End(_) => nested -= 1,
+                Event::Eof => {
+                    let name = String::from_utf8_lossy(tag.name()).into_owned();
+                    let location = locate(&self.stack, None, self.mark());
+                    return Err(Error::syntax(quick_xml::Error::UnexpectedEof(name)).at(location));
+                }
+                _ => {}
+            }
+        }
+        self.leave();
+        Ok(start..end)
+    }
+
+    /// Skips the rest of the element whose start tag was just returned by [`Scanner::next`]
+    pub(crate) fn skip(&mut self, tag: &Tag) -> Result<(), Error> {
+        if tag.empty {
+            self.pending_end = false;
+        } else if let Err((error, mark)) = self.read_to_end(tag) {
+            return Err(Error::from(error).at(locate(&self.stack, None, mark)));
+        }
+        self.leave();
+        Ok(())
+    }
+
+    /// Reads the events up to the end of the element, an error is returned together with
+    /// the position of the event that cannot be read
+    fn read_to_end(&mut self, tag: &Tag) -> Result<(), (quick_xml::Error, Mark)> {
+        let mut nested = 0;
+        loop {
+            self.buffer.clear();
+            let mark = self.mark();
+            match self.reader.read_event_into(&mut self.buffer) {
+                Ok(Event::Start(_)) => nested += 1,
+                Ok(Event::End(_)) if nested == 0 => return Ok(()),
+                Ok(Event::End(_)) => nested -= 1,
+                Ok(Event::Eof) => {
+                    let name = String::from_utf8_lossy(tag.name()).into_owned();
+                    return Err((quick_xml::Error::UnexpectedEof(name), mark));
+                }
+                Ok(_) => {}
+                Err(e) => return Err((e, mark)),
+            }
+        }
+    }
+
+    /// Decoded value of an attribute of a tag
+    pub(crate) fn attribute(&self, tag: &Tag, name: &str) -> Result<Option<String>, Error> {
+        for attribute in tag.start.attributes() {
+            let attribute = attribute.map_err(quick_xml::Error::InvalidAttr)?;
+            if attribute.key.local_name().into_inner() == name.as_bytes() {
+                let value = self.decoder.decode(&attribute.value)?;
+                let value = quick_xml::escape::unescape(&value)
+                    .map_err(quick_xml::Error::EscapeError)?
+                    .into_owned();
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// A document put together from parts of the source, e.g. to leave some elements out
+#[derive(Default)]
+pub(crate) struct Assembly {
+    text: String,
+    /// Start of each part in the text, and its position in the source
+    parts: Vec<(usize, Mark)>,
+}
+
+impl Assembly {
+    fn push(&mut self, part: &str, mark: Mark) {
+        self.parts.push((self.text.len(), mark));
+        self.text.push_str(part);
+    }
+}
+
+/// Location at the mark within the innermost open element, or its attribute
+fn locate(stack: &[Entry], attribute: Option<&str>, mark: Mark) -> Option<Location> {
+    if stack.is_empty() {
+        return None;
+    }
+    let mut path = String::new();
+    for entry in stack {
+        path.push('/');
+        path.push_str(&entry.name);
+        if entry.ordinal > 1 {
+            path.push_str(&format!("[{}]", entry.ordinal));
+        }
+    }
+    if let Some(attribute) = attribute {
+        path.push_str("/@");
+        path.push_str(attribute);
+    }
+    Some(Location {
+        offset: mark.offset,
+        line: mark.line,
+        column: mark.column,
+        path,
+    })
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\r' | '\n')
+}
+
+/// Decodes text parts of a document according to its encoding
+#[derive(Clone, Copy)]
+struct Decoder {
+    #[cfg(feature = "encoding")]
+    encoding: &'static Encoding,
+}
+
+impl Decoder {
+    #[cfg(feature = "encoding")]
+    fn detect<R: BufRead>(reader: R) -> Result<(BufReader<Source<R>>, Decoder), Error> {
+        let mut reader = BufReader::with_capacity(BUFFER_SIZE, Source::Stream(reader));
+        let detected = crate::encoding::detect(reader.fill_buf()?);
+        if detected.encoding.is_ascii_compatible() {
+            let decoder = Decoder {
+                encoding: detected.encoding,
+            };
+            return Ok((reader, decoder));
+        }
+        // the markup itself is encoded, e.g. UTF-16, so the document is decoded upfront
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        let (content, _) = crate::encoding::decode(&bytes);
+        let content = std::io::Cursor::new(content.into_owned().into_bytes());
+        let reader = BufReader::with_capacity(BUFFER_SIZE, Source::Decoded(content));
+        Ok((reader, Decoder { encoding: UTF_8 }))
+    }
+
+    #[cfg(not(feature = "encoding"))]
+    fn detect<R: BufRead>(reader: R) -> Result<(BufReader<Source<R>>, Decoder), Error> {
+        let reader = BufReader::with_capacity(BUFFER_SIZE, Source::Stream(reader));
+        Ok((reader, Decoder {}))
+    }
+
+    #[cfg(feature = "encoding")]
+    fn is_single_byte(self) -> bool {
+        self.encoding.is_single_byte()
+    }
+
+    #[cfg(not(feature = "encoding"))]
+    fn is_single_byte(self) -> bool {
+        false
+    }
+
+    /// Length of a decoded text in the source
+    #[cfg(feature = "encoding")]
+    fn encoded_len(self, text: &str) -> u64 {
+        if self.encoding == UTF_8 {
+            return text.len() as u64;
+        }
+        let (bytes, _, _) = self.encoding.encode(text);
+        bytes.len() as u64
+    }
+
+    #[cfg(not(feature = "encoding"))]
+    fn encoded_len(self, text: &str) -> u64 {
+        text.len() as u64
+    }
+
+    #[cfg(feature = "encoding")]
+    fn decode(self, bytes: &[u8]) -> Result<String, Error> {
+        let (content, _) = self.encoding.decode_without_bom_handling(bytes);
+        Ok(content.into_owned())
+    }
+
+    #[cfg(not(feature = "encoding"))]
+    fn decode(self, bytes: &[u8]) -> Result<String, Error> {
+        std::str::from_utf8(bytes)
+            .map(str::to_string)
+            .map_err(|e| Error::Encoding(e.to_string()))
+    }
+}
+
+enum Source<R> {
+    Stream(R),
+    #[cfg(feature = "encoding")]
+    Decoded(std::io::Cursor<Vec<u8>>),
+}
+
+impl<R: BufRead> Read for Source<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Source::Stream(reader) => reader.read(buf),
+            #[cfg(feature = "encoding")]
+            Source::Decoded(reader) => reader.read(buf),
+        }
+    }
+}
+
+/// Copies the consumed bytes while recording is enabled, and counts lines
+struct Recorder<R> {
+    inner: R,
+    recording: Option<Vec<u8>>,
+    counter: Counter,
+}
+
+/// Position of the next unread byte
+struct Counter {
+    /// Whether every byte is a character, otherwise the source is UTF-8
+    single_byte: bool,
+    line: usize,
+    /// In characters
+    column: usize,
+    /// Amount of bytes consumed
+    consumed: u64,
+}
+
+impl Counter {
+    fn count(&mut self, bytes: &[u8]) {
+        self.consumed += bytes.len() as u64;
+        for &b in bytes {
+            if b == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else if self.single_byte || b & 0xC0 != 0x80 {
+                self.column += 1;
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for Recorder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if let Some(recording) = &mut self.recording {
+            recording.extend_from_slice(&buf[..read]);
+        }
+        self.counter.count(&buf[..read]);
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for Recorder<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        // the consumed bytes are still in the buffer of the inner reader
+        if let Ok(buffer) = self.inner.fill_buf() {
+            let consumed = &buffer[..amount];
+            if let Some(recording) = &mut self.recording {
+                recording.extend_from_slice(consumed);
+            }
+            self.counter.count(consumed);
+        }
+        self.inner.consume(amount)
+    }
+}
@@ -0,0 +1,132 @@
+//! Numbers of a book in a series.
+//!
+//! The schema expects an integer, but real books have `1.5`, `2-3`, or `IV` there,
+//! so a number is parsed as well as possible, and the value itself is kept as is.
+
+use serde::{Deserialize, Serialize};
+
+use crate::date::roman;
+
+/// The `number` attribute of a [`Sequence`](crate::Sequence)
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub struct SequenceNumber {
+    raw: String,
+    value: Option<SequenceValue>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SequenceValue {
+    /// e.g. `3`, or `III` in roman numerals
+    Whole(i32),
+    /// A number in thousandths, e.g. 1500 for `1.5` or `1,5`
+    Fraction(i64),
+    /// A book that covers several numbers, e.g. `2-3`
+    Range(i32, i32),
+}
+
+impl SequenceNumber {
+    /// Parses a value, an invalid one is kept without a value
+    pub fn parse(raw: &str) -> SequenceNumber {
+        SequenceNumber::from(raw.to_string())
+    }
+
+    /// The value as it is in the document, it is written back on serialization
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// `None` if the value cannot be parsed
+    pub fn value(&self) -> Option<SequenceValue> {
+        self.value
+    }
+
+    /// The number if it is whole
+    pub fn whole(&self) -> Option<i32> {
+        match self.value {
+            Some(SequenceValue::Whole(number)) => Some(number),
+            _ => None,
+        }
+    }
+
+    /// A key to sort books of a series, the position in thousandths, e.g. 1500 for `1.5`.
+    /// A range is sorted by its first number, a number that cannot be parsed has no key
+    pub fn key(&self) -> Option<i64> {
+        match self.value? {
+            SequenceValue::Whole(number) | SequenceValue::Range(number, _) => {
+                Some(i64::from(number) * 1000)
+            }
+            SequenceValue::Fraction(thousandths) => Some(thousandths),
+        }
+    }
+}
+
+impl From<i32> for SequenceNumber {
+    fn from(number: i32) -> Self {
+        SequenceNumber::from(SequenceValue::Whole(number))
+    }
+}
+
+impl From<SequenceValue> for SequenceNumber {
+    fn from(value: SequenceValue) -> Self {
+        let raw = match value {
+            SequenceValue::Whole(number) => number.to_string(),
+            SequenceValue::Fraction(thousandths) => {
+                let sign = if thousandths < 0 { "-" } else { "" };
+                let thousandths = thousandths.unsigned_abs();
+                let fraction = format!("{:03}", thousandths % 1000);
+                let fraction = fraction.trim_end_matches('0');
+                let fraction = if fraction.is_empty() { "0" } else { fraction };
+                format!("{sign}{}.{fraction}", thousandths / 1000)
+            }
+            SequenceValue::Range(first, last) => format!("{first}-{last}"),
+        };
+        SequenceNumber {
+            raw,
+            value: Some(value),
+        }
+    }
+}
+
+impl From<String> for SequenceNumber {
+    fn from(raw: String) -> Self {
+        let value = parse_value(raw.trim());
+        SequenceNumber { raw, value }
+    }
+}
+
+impl From<SequenceNumber> for String {
+    fn from(number: SequenceNumber) -> Self {
+        number.raw
+    }
+}
+
+fn parse_value(value: &str) -> Option<SequenceValue> {
+    let value = value.trim_start_matches(['#', '№']).trim_start();
+    if let Some(number) = whole(value) {
+        return Some(SequenceValue::Whole(number));
+    }
+    if let Some((whole, fraction)) = value.split_once(['.', ',']) {
+        let digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+        if digits(whole) && digits(fraction) {
+            // e.g. `1.25` is 1250 thousandths, digits after the third one are dropped
+            let fraction = format!("{fraction:0<3}");
+            let thousandths = whole
+                .parse::<i64>()
+                .ok()?
+                .checked_mul(1000)?
+                .checked_add(fraction[..3].parse().ok()?)?;
+            return Some(SequenceValue::Fraction(thousandths));
+        }
+    }
+    let (first, last) = value.split_once(['-', '–', '—'])?;
+    let (first, last) = (whole(first.trim())?, whole(last.trim())?);
+    (first <= last).then_some(SequenceValue::Range(first, last))
+}
+
+fn whole(value: &str) -> Option<i32> {
+    value.parse().ok().or_else(|| {
+        let number = roman(&value.to_lowercase())?;
+        i32::try_from(number).ok()
+    })
+}
@@ -0,0 +1,323 @@
+//! Writing of FB2 documents.
+//!
+//! The book is serialized by serde first, then the events are rewritten: the root gets
+//! the namespaces, the attributes of links and languages get their prefixes, the placeholders
+//! of the comments and instructions are turned back, and the elements are indented.
+
+use std::borrow::Cow;
+use std::io::Write;
+
+#[cfg(feature = "encoding")]
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use quick_xml::escape::unescape;
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::{BytesStart, BytesText, Event};
+use quick_xml::name::QName;
+use quick_xml::{Reader, Writer};
+
+use crate::extras::{self, COMMENT_PLACEHOLDER, INSTRUCTION_PLACEHOLDER};
+use crate::{xlink, Error, FictionBook};
+
+/// Namespace of the FB2 elements
+pub const FB2_NAMESPACE: &str = "http://www.gribuser.ru/xml/fictionbook/2.0";
+/// Namespace of the link attributes, e.g. `l:href` of images
+pub const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+/// How a book is written by [`to_writer_with_options`], e.g.
+/// `WriteOptions::default().indent("  ").line_ending(LineEnding::CrLf)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct WriteOptions {
+    /// Indentation of a nesting level, e.g. `"  "`, or `None` to write the elements
+    /// one after another. An element with text, e.g. a paragraph, is written on a single line
+    /// to keep the text as is
+    pub indent: Option<String>,
+    pub line_ending: LineEnding,
+    /// Encoding of the document, the characters it can't represent are written as
+    /// character references. UTF-16 is written with a byte order mark
+    #[cfg(feature = "encoding")]
+    pub encoding: &'static Encoding,
+}
+
+// derivable only without the encoding
+#[allow(clippy::derivable_impls)]
+impl Default for WriteOptions {
+    fn default() -> Self {
+        WriteOptions {
+            indent: None,
+            line_ending: LineEnding::default(),
+            #[cfg(feature = "encoding")]
+            encoding: UTF_8,
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Indents a nesting level by `indent`, see [`WriteOptions::indent`]
+    pub fn indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = Some(indent.into());
+        self
+    }
+
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Writes the document in an encoding, see [`WriteOptions::encoding`]
+    #[cfg(feature = "encoding")]
+    pub fn encoding(mut self, encoding: &'static Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+}
+
+/// Line breaks written by [`to_writer_with_options`], the ones in the text are converted as well
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+/// Serializes a book into an FB2 document in UTF-8, together with the kept comments
+/// and processing instructions
+pub fn to_writer<W: Write>(book: &FictionBook, writer: W) -> Result<(), Error> {
+    to_writer_with_options(book, writer, &WriteOptions::default())
+}
+
+/// Serializes a book into an FB2 document, see [`to_writer`]
+pub fn to_writer_with_options<W: Write>(
+    book: &FictionBook,
+    mut writer: W,
+    options: &WriteOptions,
+) -> Result<(), Error> {
+    let document = write_document(book, options)?;
+    writer.write_all(&encode(&document, options))?;
+    Ok(())
+}
+
+/// Serializes a book into an FB2 document, see [`to_writer`]
+pub fn to_string(book: &FictionBook) -> Result<String, Error> {
+    write_document(book, &WriteOptions::default())
+}
+
+fn write_document(book: &FictionBook, options: &WriteOptions) -> Result<String, Error> {
+    // the book is serialized as a map because of the extras, which has no name of its own
+    let content = extras::with_misc(|| quick_xml::se::to_string_with_root("FictionBook", book))?;
+    let events = read_events(&content, xlink::prefix(&book.extras))?;
+    let mut output = Writer::new(Vec::with_capacity(content.len()));
+    output.get_mut().extend_from_slice(
+        format!(
+            r#"<?xml version="1.0" encoding="{}"?>"#,
+            encoding_name(options)
+        )
+        .as_bytes(),
+    );
+    output.get_mut().push(b'\n');
+    write_events(&events, &mut output, options.indent.as_deref())?;
+
+    let document = String::from_utf8(output.into_inner()).expect("the serializer writes UTF-8");
+    Ok(match options.line_ending {
+        LineEnding::Lf => document,
+        LineEnding::CrLf => to_crlf(&document),
+    })
+}
+
+/// Reads the serialized events, turns the placeholders back, and renames the attributes
+fn read_events<'a>(content: &'a str, xlink: &str) -> Result<Vec<Event<'a>>, Error> {
+    let names = LinkNames {
+        declaration: format!("xmlns:{xlink}"),
+        href: format!("{xlink}:href"),
+        kind: format!("{xlink}:type"),
+    };
+    let mut reader = Reader::from_str(content);
+    let mut events = vec![];
+    let mut root = true;
+    loop {
+        let event = match reader.read_event()? {
+            Event::Start(tag) if is_placeholder(tag.name().as_ref()) => {
+                let text = reader.read_text(tag.name())?;
+                misc(tag.name().as_ref(), &text)?
+            }
+            Event::Empty(tag) if is_placeholder(tag.name().as_ref()) => {
+                misc(tag.name().as_ref(), "")?
+            }
+            Event::Start(tag) => Event::Start(qualify(tag, std::mem::take(&mut root), &names)?),
+            Event::Empty(tag) => Event::Empty(qualify(tag, std::mem::take(&mut root), &names)?),
+            Event::Eof => break,
+            event => event,
+        };
+        events.push(event);
+    }
+    Ok(events)
+}
+
+fn is_placeholder(name: &[u8]) -> bool {
+    name == COMMENT_PLACEHOLDER.as_bytes() || name == INSTRUCTION_PLACEHOLDER.as_bytes()
+}
+
+fn misc<'a>(name: &[u8], text: &str) -> Result<Event<'a>, Error> {
+    let text =
+        BytesText::from_escaped(unescape(text).map_err(quick_xml::Error::from)?.into_owned());
+    if name == COMMENT_PLACEHOLDER.as_bytes() {
+        Ok(Event::Comment(text))
+    } else {
+        Ok(Event::PI(text))
+    }
+}
+
+/// Qualified names of the xlink namespace declaration and attributes
+struct LinkNames {
+    declaration: String,
+    href: String,
+    kind: String,
+}
+
+/// Declares the namespaces on the root, and prefixes the attributes that belong to them
+fn qualify(
+    tag: BytesStart<'_>,
+    root: bool,
+    names: &LinkNames,
+) -> Result<BytesStart<'static>, Error> {
+    let name = tag.name();
+    let mut qualified = BytesStart::new(String::from_utf8_lossy(name.as_ref()).into_owned());
+    if root {
+        qualified.push_attribute(("xmlns", FB2_NAMESPACE));
+        qualified.push_attribute((names.declaration.as_str(), XLINK_NAMESPACE));
+    }
+    for attribute in tag.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        let key = match (name.as_ref(), attribute.key.as_ref()) {
+            (_, b"lang") => b"xml:lang".as_slice(),
+            (b"image" | b"a" | b"part", b"href") => names.href.as_bytes(),
+            (b"image" | b"part", b"type") => names.kind.as_bytes(),
+            (_, key) => key,
+        };
+        qualified.push_attribute(Attribute {
+            key: QName(key),
+            value: attribute.value,
+        });
+    }
+    Ok(qualified)
+}
+
+/// Element being written
+struct Open {
+    /// Whether the content is written as is, without indentation
+    inline: bool,
+    has_children: bool,
+}
+
+fn write_events(
+    events: &[Event<'_>],
+    output: &mut Writer<Vec<u8>>,
+    indent: Option<&str>,
+) -> Result<(), Error> {
+    let inline = inline_elements(events);
+    let mut stack: Vec<Open> = vec![];
+    for (index, event) in events.iter().enumerate() {
+        let closed = match event {
+            Event::End(_) => stack.pop(),
+            _ => None,
+        };
+        if let Some(indent) = indent {
+            let line_break = match (&closed, stack.last_mut()) {
+                (Some(closed), _) => !closed.inline && closed.has_children,
+                (None, Some(parent)) => {
+                    parent.has_children = true;
+                    !parent.inline
+                }
+                (None, None) => false,
+            };
+            if line_break {
+                output.get_mut().push(b'\n');
+                output
+                    .get_mut()
+                    .extend_from_slice(indent.repeat(stack.len()).as_bytes());
+            }
+        }
+        if let Event::Start(_) = event {
+            let parent_inline = stack.last().is_some_and(|parent| parent.inline);
+            stack.push(Open {
+                inline: parent_inline || inline[index],
+                has_children: false,
+            });
+        }
+        output.write_event(event)?;
+    }
+    Ok(())
+}
+
+/// Tells for each start of an element whether it has text, then the whitespaces between
+/// its children may be significant
+fn inline_elements(events: &[Event<'_>]) -> Vec<bool> {
+    let mut inline = vec![false; events.len()];
+    let mut starts = vec![];
+    for (index, event) in events.iter().enumerate() {
+        match event {
+            Event::Start(tag) => {
+                inline[index] = matches!(
+                    tag.name().as_ref(),
+                    b"p" | b"v" | b"subtitle" | b"text-author" | b"td" | b"th"
+                );
+                starts.push(index);
+            }
+            Event::End(_) => {
+                starts.pop();
+            }
+            Event::Text(_) | Event::CData(_) => {
+                if let Some(&start) = starts.last() {
+                    inline[start] = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    inline
+}
+
+fn to_crlf(text: &str) -> String {
+    let mut converted = String::with_capacity(text.len() + text.len() / 32);
+    let mut previous = None;
+    for c in text.chars() {
+        if c == '\n' && previous != Some('\r') {
+            converted.push('\r');
+        }
+        converted.push(c);
+        previous = Some(c);
+    }
+    converted
+}
+
+#[cfg(feature = "encoding")]
+fn encoding_name(options: &WriteOptions) -> &'static str {
+    if options.encoding == UTF_16LE || options.encoding == UTF_16BE {
+        "UTF-16"
+    } else {
+        options.encoding.name()
+    }
+}
+
+#[cfg(not(feature = "encoding"))]
+fn encoding_name(_: &WriteOptions) -> &'static str {
+    "UTF-8"
+}
+
+#[cfg(feature = "encoding")]
+fn encode<'a>(document: &'a str, options: &WriteOptions) -> Cow<'a, [u8]> {
+    let units = || [0xfeff].into_iter().chain(document.encode_utf16());
+    if options.encoding == UTF_16LE {
+        Cow::Owned(units().flat_map(u16::to_le_bytes).collect())
+    } else if options.encoding == UTF_16BE {
+        Cow::Owned(units().flat_map(u16::to_be_bytes).collect())
+    } else {
+        let (bytes, _, _) = options.encoding.encode(document);
+        bytes
+    }
+}
+
+#[cfg(not(feature = "encoding"))]
+fn encode<'a>(document: &'a str, _: &WriteOptions) -> Cow<'a, [u8]> {
+    Cow::Borrowed(document.as_bytes())
+}
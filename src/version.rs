@@ -0,0 +1,98 @@
+//! Versions of a document.
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// The `version` of a [`DocumentInfo`](crate::DocumentInfo), e.g. `1.0` or `1.0.1`.
+///
+/// Versions are compared part by part, so `1.10` is newer than `1.9`, while `1.0` and `1.0.0`
+/// are only ordered by the text. A version that cannot be parsed is older than any other
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub struct Version {
+    raw: String,
+    parts: Option<Vec<u32>>,
+}
+
+impl Version {
+    /// A version of numbers separated by dots, e.g. `1.0.1`
+    pub fn new(parts: Vec<u32>) -> Version {
+        let raw = parts
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(".");
+        Version {
+            raw,
+            parts: Some(parts),
+        }
+    }
+
+    /// Parses a value, an invalid one is kept without parts
+    pub fn parse(raw: &str) -> Version {
+        Version::from(raw.to_string())
+    }
+
+    /// The value as it is in the document, it is written back on serialization
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// Numbers separated by dots, `None` if the value cannot be parsed
+    pub fn parts(&self) -> Option<&[u32]> {
+        self.parts.as_deref()
+    }
+}
+
+impl From<String> for Version {
+    fn from(raw: String) -> Self {
+        let value = raw.trim();
+        let value = value.strip_prefix(['v', 'V']).unwrap_or(value);
+        // a comma is a decimal separator in Russian, e.g. `1,1`
+        let parts = value
+            .split(['.', ','])
+            .map(|part| {
+                let digits = !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+                part.parse().ok().filter(|_| digits)
+            })
+            .collect();
+        Version { raw, parts }
+    }
+}
+
+impl From<Version> for String {
+    fn from(version: Version) -> Self {
+        version.raw
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let parts = match (&self.parts, &other.parts) {
+            (Some(parts), Some(others)) => {
+                let len = parts.len().max(others.len());
+                let padded = |parts: &[u32], i: usize| parts.get(i).copied().unwrap_or(0);
+                (0..len)
+                    .map(|i| padded(parts, i).cmp(&padded(others, i)))
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            }
+            (parts, others) => parts.is_some().cmp(&others.is_some()),
+        };
+        parts.then_with(|| self.raw.cmp(&other.raw))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -0,0 +1,82 @@
+//! Resolution of the link attributes by namespace rather than by prefix.
+//!
+//! Serde matches attributes by their local names, so `l:href`, `xlink:href`, and a plain `href`
+//! are the same to it, and an element having two of them fails to deserialize. Before
+//! the deserialization, the attribute bound to the xlink namespace is chosen, and the names
+//! of the others are spoiled in the copy of the text, so serde ignores them.
+
+use quick_xml::events::BytesStart;
+use quick_xml::name::{Namespace, ResolveResult};
+use quick_xml::NsReader;
+
+use crate::location::attribute_names;
+use crate::{Extras, XLINK_NAMESPACE};
+
+/// Offsets of the last bytes of the names of the attributes that must be ignored,
+/// `text` is the start tag at `start`
+pub(crate) fn ignored_attributes(
+    reader: &NsReader<&[u8]>,
+    tag: &BytesStart,
+    text: &str,
+    start: usize,
+) -> Result<Vec<usize>, quick_xml::Error> {
+    let element = tag.local_name();
+    if !matches!(element.as_ref(), b"image" | b"a" | b"part") {
+        return Ok(vec![]);
+    }
+    // the best attribute for each name so far, a lower rank is better
+    let mut best: Vec<(&[u8], u8, usize)> = vec![];
+    let mut ignored = vec![];
+    for (attribute, span) in tag.attributes().zip(attribute_names(text)) {
+        let attribute = attribute?;
+        let (namespace, name) = reader.resolve_attribute(attribute.key);
+        let xlink = matches!(namespace, ResolveResult::Bound(Namespace(ns)) if ns == XLINK_NAMESPACE.as_bytes());
+        let rank = match (element.as_ref(), name.as_ref()) {
+            // the type of a link is the type of a note, e.g. `type="note"`, not the xlink one
+            (b"a", b"type") => match namespace {
+                ResolveResult::Unbound => 0,
+                ResolveResult::Unknown(_) => 1,
+                _ if xlink => 3,
+                ResolveResult::Bound(_) => 2,
+            },
+            (_, b"href") | (b"image" | b"part", b"type") => match namespace {
+                _ if xlink => 0,
+                ResolveResult::Unbound => 1,
+                // an undeclared prefix, usually `l:`
+                ResolveResult::Unknown(_) => 2,
+                // a misspelled namespace is better than nothing
+                ResolveResult::Bound(_) => 3,
+            },
+            _ => continue,
+        };
+        let end = start + span.end - 1;
+        match best.iter_mut().find(|(n, _, _)| *n == name.into_inner()) {
+            Some(found) if found.1 <= rank => ignored.push(end),
+            Some(found) => {
+                ignored.push(found.2);
+                *found = (name.into_inner(), rank, end);
+            }
+            None => best.push((name.into_inner(), rank, end)),
+        }
+    }
+    Ok(ignored)
+}
+
+/// Spoils the name of an ignored attribute keeping its length
+pub(crate) fn ignore(text: &mut [u8], end: usize) {
+    text[end] = text[end].to_ascii_uppercase();
+}
+
+/// Prefix the xlink namespace is written with, `l` unless the root binds it to another namespace
+pub(crate) fn prefix(root: &Extras) -> &'static str {
+    let taken = |prefix: &str| {
+        root.attributes
+            .iter()
+            .any(|(name, _)| name.strip_prefix("xmlns:") == Some(prefix))
+    };
+    if taken("l") {
+        "xlink"
+    } else {
+        "l"
+    }
+}
@@ -0,0 +1,115 @@
+//! Reading and writing of `.fb2.zip` archives, a common way to store FB2 books.
+
+use std::fs::File;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{Error, FictionBook};
+
+const FB2_EXTENSION: &str = ".fb2";
+const ZIP_EXTENSION: &str = ".zip";
+
+/// The size in the header of an entry is not trusted beyond this, the buffer grows as it's read
+const MAX_PREALLOCATION: u64 = 16 * 1024 * 1024;
+
+/// An archive with one or more FB2 books
+pub struct Archive<R> {
+    archive: ZipArchive<R>,
+    /// Indices and names of the FB2 entries in the archive order
+    entries: Vec<(usize, String)>,
+}
+
+impl<R: Read + Seek> Archive<R> {
+    /// Opens an archive and finds all FB2 entries in it
+    pub fn new(reader: R) -> Result<Self, Error> {
+        let mut archive = ZipArchive::new(reader)?;
+        let mut entries = vec![];
+        for index in 0..archive.len() {
+            let file = archive.by_index_raw(index)?;
+            if !file.is_dir() && is_fb2_entry(file.name()) {
+                entries.push((index, file.name().to_string()));
+            }
+        }
+        Ok(Archive { archive, entries })
+    }
+
+    /// Number of FB2 entries in the archive
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Names of the FB2 entries in the archive order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(_, name)| name.as_str())
+    }
+
+    /// Deserializes the book stored in the `index`-th FB2 entry
+    pub fn book(&mut self, index: usize) -> Result<FictionBook, Error> {
+        let (entry_index, _) = self
+            .entries
+            .get(index)
+            .ok_or(Error::Archive(zip::result::ZipError::FileNotFound))?;
+        let mut file = self.archive.by_index(*entry_index)?;
+        let mut bytes = Vec::with_capacity(file.size().min(MAX_PREALLOCATION) as usize);
+        file.read_to_end(&mut bytes)?;
+        crate::from_slice(&bytes)
+    }
+
+    /// Deserializes all books of the archive one by one
+    pub fn books(&mut self) -> impl Iterator<Item = Result<FictionBook, Error>> + '_ {
+        (0..self.len()).map(|index| self.book(index))
+    }
+}
+
+/// Deserializes the first book of an archive
+pub fn from_reader<R: Read + Seek>(reader: R) -> Result<FictionBook, Error> {
+    Archive::new(reader)?.book(0)
+}
+
+/// Deserializes the first book of an archive file
+pub fn from_path<P: AsRef<Path>>(path: P) -> Result<FictionBook, Error> {
+    from_reader(File::open(path)?)
+}
+
+/// Serializes a book into a compressed archive with a single entry named `entry_name`
+pub fn to_writer<W: Write + Seek>(
+    book: &FictionBook,
+    entry_name: &str,
+    writer: W,
+) -> Result<(), Error> {
+    let mut archive = ZipWriter::new(writer);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    archive.start_file(entry_name, options)?;
+    crate::ser::to_writer(book, &mut archive)?;
+    archive.finish()?;
+    Ok(())
+}
+
+/// Serializes a book into a compressed archive file. The entry is named after the file,
+/// e.g. `book.fb2.zip` contains `book.fb2`
+pub fn to_path<P: AsRef<Path>>(book: &FictionBook, path: P) -> Result<(), Error> {
+    let path = path.as_ref();
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let stem = file_name.strip_suffix(ZIP_EXTENSION).unwrap_or(&file_name);
+    let entry_name = if stem.ends_with(FB2_EXTENSION) {
+        stem.to_string()
+    } else {
+        format!("{stem}{FB2_EXTENSION}")
+    };
+    to_writer(book, &entry_name, File::create(path)?)
+}
+
+fn is_fb2_entry(name: &str) -> bool {
+    // metadata of archives created on macOS
+    !name.starts_with("__MACOSX/") && name.to_lowercase().ends_with(FB2_EXTENSION)
+}
@@ -0,0 +1,99 @@
+use fb2::{Binary, ContentTypeMismatch, Error, ImageFormat};
+
+const JPEG: &[u8] = b"\xFF\xD8\xFF\xE0\x00\x10JFIF";
+const PNG: &[u8] = b"\x89PNG\r\n\x1A\n";
+
+fn binary(content_type: &str, content: &str) -> Binary {
+    Binary {
+        id: "image".into(),
+        content_type: content_type.into(),
+        content: content.into(),
+    }
+}
+
+#[test]
+fn decode_formatted_content() {
+    let indented = binary("image/jpeg", "\n    /9j/4AAQ\n\t SkZJRg==\n");
+    assert_eq!(indented.decode().unwrap(), JPEG);
+    let non_breaking = binary("image/jpeg", "/9j/4AAQ\u{a0}SkZJRg");
+    assert_eq!(non_breaking.decode().unwrap(), JPEG);
+}
+
+#[test]
+fn decode_wrong_padding() {
+    assert_eq!(binary("image/png", "iVBORw0KGgo").decode().unwrap(), PNG);
+    assert_eq!(binary("image/png", "iVBORw0KGgo===").decode().unwrap(), PNG);
+    // two contents concatenated with their padding
+    assert_eq!(
+        binary("image/png", "iVBORw==DQoaCg==").decode().unwrap(),
+        PNG
+    );
+}
+
+#[test]
+fn decode_url_safe_alphabet() {
+    assert_eq!(binary("", "-_-_").decode().unwrap(), b"\xFB\xFF\xBF");
+}
+
+#[test]
+fn garbage_is_binary_error() {
+    let error = binary("image/png", "iVBOR*0KGgo").decode().unwrap_err();
+    assert!(matches!(error, Error::Binary(_)), "{error:?}");
+}
+
+#[test]
+fn sniff_formats() {
+    for (bytes, format) in [
+        (JPEG, ImageFormat::Jpeg),
+        (PNG, ImageFormat::Png),
+        (b"GIF89a\x01\x00".as_slice(), ImageFormat::Gif),
+        (b"RIFF\x24\x00\x00\x00WEBPVP8 ", ImageFormat::WebP),
+        (
+            br#"<?xml version="1.0"?><!-- drawn --><svg xmlns="http://www.w3.org/2000/svg"/>"#,
+            ImageFormat::Svg,
+        ),
+        (b"\xEF\xBB\xBF  <svg/>", ImageFormat::Svg),
+    ] {
+        assert_eq!(ImageFormat::sniff(bytes), Some(format), "{bytes:?}");
+    }
+    assert_eq!(ImageFormat::sniff(b"<html><body/></html>"), None);
+    assert_eq!(ImageFormat::sniff(b""), None);
+}
+
+#[test]
+fn content_types() {
+    for (content_type, format) in [
+        ("image/jpeg", ImageFormat::Jpeg),
+        ("image/JPG", ImageFormat::Jpeg),
+        ("image/x-png", ImageFormat::Png),
+        ("image/svg+xml; charset=utf-8", ImageFormat::Svg),
+    ] {
+        assert_eq!(ImageFormat::from_content_type(content_type), Some(format));
+    }
+    assert_eq!(
+        ImageFormat::from_content_type("application/octet-stream"),
+        None
+    );
+    assert_eq!(ImageFormat::Png.content_type(), "image/png");
+}
+
+#[test]
+fn content_type_mismatch() {
+    assert_eq!(
+        binary("image/jpeg", "iVBORw0KGgo").sniff(),
+        Some(ImageFormat::Png)
+    );
+    assert_eq!(
+        binary("image/jpeg", "iVBORw0KGgo").content_type_mismatch(),
+        Some(ContentTypeMismatch {
+            declared: "image/jpeg".into(),
+            actual: ImageFormat::Png,
+        })
+    );
+    assert_eq!(
+        binary("image/jpg", "/9j/4AAQ").content_type_mismatch(),
+        None
+    );
+    // unknown content can't contradict its type
+    assert_eq!(binary("image/png", "AAAA").content_type_mismatch(), None);
+}
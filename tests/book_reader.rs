@@ -0,0 +1,101 @@
+use fb2::{BookItem, BookReader, Error, Extras, FictionBook};
+
+const PATHS: [&str; 4] = [
+    "tests/resources/complex.fb2",
+    "tests/resources/many_bodies.fb2",
+    "tests/resources/minimal.fb2",
+    "tests/resources/non_standard_genres.fb2",
+];
+
+const SLOPPY: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">
+    <description>
+        <title-info>
+            <book-title>Title</book-title>
+        </title-info>
+    </description>
+    <body name="notes">
+        <title><p>Notes</p></title>
+        <p>Stray paragraph</p>
+        <title><p>Second title</p></title>
+        <body><section><p>Nested</p></section><section><p>Bodies</p></section></body>
+        Stray text
+    </body>
+    <body><section><p>Last</p></section></body>
+    <binary id="cover.jpg" content-type="image/jpeg">/9j/4AAQSkZJRg==</binary>
+</FictionBook>"##;
+
+fn read(bytes: &[u8]) -> Result<Vec<BookItem>, Error> {
+    BookReader::new(bytes)?.collect()
+}
+
+fn assemble(items: Vec<BookItem>) -> FictionBook {
+    let mut stylesheets = vec![];
+    let mut description = None;
+    let mut bodies = vec![];
+    let mut binaries = vec![];
+    for item in items {
+        match item {
+            BookItem::Stylesheet(stylesheet) => stylesheets.push(stylesheet),
+            BookItem::Description(d) => description = Some(d),
+            BookItem::Body(body) => {
+                assert!(body.sections.is_empty());
+                bodies.push(body);
+            }
+            BookItem::Section(section) => bodies.last_mut().unwrap().sections.push(section),
+            BookItem::Binary(binary) => binaries.push(binary),
+        }
+    }
+    FictionBook {
+        stylesheets,
+        description: description.unwrap(),
+        bodies,
+        binaries,
+        extras: Extras::default(),
+    }
+}
+
+#[test]
+fn items_assemble_into_full_book() {
+    for path in PATHS {
+        let bytes = std::fs::read(path).unwrap();
+        let items = read(&bytes).unwrap();
+        assert_eq!(assemble(items), fb2::from_slice(&bytes).unwrap(), "{path}");
+    }
+}
+
+#[test]
+fn sloppy_bodies_are_normalized() {
+    let items = read(SLOPPY.as_bytes()).unwrap();
+    assert_eq!(items.len(), 10);
+    assert!(matches!(items[0], BookItem::Description(_)));
+    assert!(matches!(&items[1], BookItem::Body(body) if body.title.is_some()));
+    assert!(matches!(items[7], BookItem::Body(_)));
+    assert!(matches!(items[9], BookItem::Binary(_)));
+    assert_eq!(
+        assemble(items),
+        fb2::from_str(SLOPPY).unwrap(),
+        "sections of a stream differ from sections of a book"
+    );
+}
+
+#[test]
+fn iteration_stops_after_error() {
+    let content = SLOPPY.replace("<p>Nested</p>", "<p>Nested");
+    let mut reader = BookReader::new(content.as_bytes()).unwrap();
+    assert!(matches!(reader.next(), Some(Ok(BookItem::Description(_)))));
+    assert!(matches!(reader.next(), Some(Ok(BookItem::Body(_)))));
+    assert!(matches!(reader.next(), Some(Ok(BookItem::Section(_)))));
+    assert!(matches!(reader.next(), Some(Ok(BookItem::Section(_)))));
+    assert!(matches!(reader.next(), Some(Err(Error::Syntax(_)))));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn missing_body_is_structure_error() {
+    let start = SLOPPY.find("<body name").unwrap();
+    let end = SLOPPY.find("<binary").unwrap();
+    let content = format!("{}{}", &SLOPPY[..start], &SLOPPY[end..]);
+    let error = read(content.as_bytes()).unwrap_err();
+    assert!(matches!(error, Error::Structure(_)), "{error:?}");
+}
@@ -0,0 +1,78 @@
+use fb2::{ExtraNode, Misc, SectionPart};
+
+const BOOK: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- converted by a script -->
+<?xml-stylesheet href="book.css"?>
+<FictionBook>
+    <description>
+        <title-info>
+            <!-- check the title -->
+            <book-title>Title</book-title>
+            <author><first-name>Ivan</first-name><!-- the middle name is unknown --></author>
+        </title-info>
+    </description>
+    <body>
+        <section>
+            <!-- chapter 1 -->
+            <p>Some<!-- a typo? --> text<v:note>note <!-- in a note --></v:note></p>
+        </section>
+    </body>
+</FictionBook>
+<!-- end -->"#;
+
+fn comment(text: &str) -> Misc {
+    Misc::Comment(text.into())
+}
+
+#[test]
+fn comments_are_kept_on_demand() {
+    let book = fb2::from_str(BOOK).unwrap();
+    assert!(book.extras.is_empty());
+
+    let book = fb2::from_str_with_comments(BOOK).unwrap();
+    assert_eq!(
+        book.extras.misc,
+        [
+            comment(" converted by a script "),
+            Misc::ProcessingInstruction(r#"xml-stylesheet href="book.css""#.into()),
+            comment(" end "),
+        ]
+    );
+    assert_eq!(
+        book.description.title_info.extras.misc,
+        [
+            comment(" check the title "),
+            comment(" the middle name is unknown ")
+        ]
+    );
+    let section = &book.bodies[0].sections[0];
+    assert_eq!(section.extras.misc, [comment(" chapter 1 ")]);
+    let SectionPart::Paragraph(paragraph) = &section.content.as_ref().unwrap().content[0] else {
+        panic!("{section:?}");
+    };
+    assert_eq!(paragraph.extras.misc, [comment(" a typo? ")]);
+    assert_eq!(
+        paragraph.extras.elements[0].children[1],
+        ExtraNode::Misc(comment(" in a note "))
+    );
+}
+
+#[test]
+fn comments_are_written_back() {
+    let book = fb2::from_str_with_comments(BOOK).unwrap();
+    let xml = fb2::to_string(&book).unwrap();
+    assert!(
+        xml.contains("<section><p>Some text<v:note>note <!-- in a note --></v:note><!-- a typo? --></p><!-- chapter 1 --></section>"),
+        "{xml}"
+    );
+    assert!(
+        xml.ends_with(r#"<!-- converted by a script --><?xml-stylesheet href="book.css"?><!-- end --></FictionBook>"#),
+        "{xml}"
+    );
+    assert_eq!(fb2::from_str_with_comments(&xml).unwrap(), book);
+
+    // serde alone can't write comments, so they are skipped
+    let section = &book.bodies[0].sections[0];
+    let xml = quick_xml::se::to_string_with_root("section", section).unwrap();
+    assert!(!xml.contains("chapter 1"), "{xml}");
+}
@@ -0,0 +1,182 @@
+use chrono::{FixedOffset, NaiveDate};
+
+use fb2::{Confidence, Date, DateRange, IsoDate, PartialDate};
+
+fn book_with_date(value: &str) -> String {
+    format!(
+        r#"<FictionBook>
+    <description>
+        <title-info>
+            <book-title>Title</book-title>
+            <date value="{value}">display</date>
+        </title-info>
+    </description>
+    <body><section><p>Text</p></section></body>
+</FictionBook>"#
+    )
+}
+
+fn parse_date(value: &str) -> Date {
+    let book = fb2::from_str(&book_with_date(value)).unwrap();
+    book.description.title_info.date.unwrap()
+}
+
+#[test]
+fn dates_of_different_precision() {
+    let date = |value| parse_date(value).iso_date.unwrap().date();
+    assert_eq!(date("1953"), Some(PartialDate::Year(1953)));
+    assert_eq!(
+        date("1953-05"),
+        Some(PartialDate::YearMonth {
+            year: 1953,
+            month: 5
+        })
+    );
+    assert_eq!(
+        date("1953-05-01"),
+        Some(PartialDate::Full(
+            NaiveDate::from_ymd_opt(1953, 5, 1).unwrap()
+        ))
+    );
+    assert_eq!(
+        date("1953-05-01T12:00:00"),
+        Some(PartialDate::Full(
+            NaiveDate::from_ymd_opt(1953, 5, 1).unwrap()
+        ))
+    );
+}
+
+#[test]
+fn dates_with_timezone() {
+    let date = parse_date("1953-05-01+03:00").iso_date.unwrap();
+    assert_eq!(date.naive_date(), NaiveDate::from_ymd_opt(1953, 5, 1));
+    assert_eq!(date.timezone(), FixedOffset::east_opt(3 * 3600));
+
+    let date = parse_date("1953-05-01-05:30").iso_date.unwrap();
+    assert_eq!(date.naive_date(), NaiveDate::from_ymd_opt(1953, 5, 1));
+    assert_eq!(date.timezone(), FixedOffset::west_opt(5 * 3600 + 30 * 60));
+
+    let date = parse_date("1953Z").iso_date.unwrap();
+    assert_eq!(date.date(), Some(PartialDate::Year(1953)));
+    assert_eq!(date.timezone(), FixedOffset::east_opt(0));
+}
+
+#[test]
+fn invalid_dates_are_kept_raw() {
+    for value in ["1953-13", "1953-02-30", "весна 1953"] {
+        let date = parse_date(value).iso_date.unwrap();
+        assert_eq!(date.raw(), value);
+        assert_eq!(date.date(), None, "{value}");
+    }
+    let (_, repairs) = fb2::from_str_with_repairs(&book_with_date("весна 1953")).unwrap();
+    assert_eq!(repairs.len(), 1);
+    assert_eq!(
+        repairs[0].to_string(),
+        "5:19 /FictionBook/description/title-info/date/@value: invalid value of `@value` ignored"
+    );
+}
+
+#[test]
+fn raw_value_is_serialized_back() {
+    for value in [" 1953-05-01+03:00", "1953-5-1", "весна 1953"] {
+        let date = parse_date(value);
+        assert_eq!(date.iso_date.as_ref().unwrap().raw(), value);
+        let xml = quick_xml::se::to_string_with_root("date", &date).unwrap();
+        assert_eq!(xml, format!(r#"<date value="{value}">display</date>"#));
+    }
+    assert_eq!(
+        IsoDate::parse("1953-05").date().unwrap().to_string(),
+        "1953-05"
+    );
+}
+
+#[test]
+fn dates_from_values() {
+    let may = NaiveDate::from_ymd_opt(1953, 5, 1).unwrap();
+    assert_eq!(IsoDate::from(may).raw(), "1953-05-01");
+    let date = IsoDate::new(
+        PartialDate::YearMonth {
+            year: 1953,
+            month: 5,
+        },
+        FixedOffset::east_opt(3 * 3600),
+    );
+    assert_eq!(date.raw(), "1953-05+03:00");
+    assert_eq!(date, IsoDate::parse("1953-05+03:00"));
+}
+
+fn interpret(text: &str) -> (String, String, Confidence) {
+    let range = DateRange::interpret(text).unwrap_or_else(|| panic!("{text}"));
+    (
+        range.start.to_string(),
+        range.end.to_string(),
+        range.confidence,
+    )
+}
+
+#[test]
+fn display_dates_are_interpreted() {
+    use Confidence::*;
+    let cases = [
+        ("1953", "1953-01-01", "1953-12-31", High),
+        ("1953 г.", "1953-01-01", "1953-12-31", High),
+        ("01.05.1953", "1953-05-01", "1953-05-01", High),
+        ("1 мая 1953 года", "1953-05-01", "1953-05-01", High),
+        ("May 1, 1953", "1953-05-01", "1953-05-01", High),
+        ("февраль 1952", "1952-02-01", "1952-02-29", High),
+        ("1948-53", "1948-01-01", "1953-12-31", High),
+        ("1948 — 1953 гг.", "1948-01-01", "1953-12-31", High),
+        ("с 1948 по 1953", "1948-01-01", "1953-12-31", High),
+        ("весна 1921", "1921-03-01", "1921-05-31", High),
+        ("winter 1921", "1920-12-01", "1921-02-28", Medium),
+        ("1920-е годы", "1920-01-01", "1929-12-31", High),
+        ("1920s", "1920-01-01", "1929-12-31", High),
+        ("XIX век", "1801-01-01", "1900-12-31", High),
+        ("ХIХ в.", "1801-01-01", "1900-12-31", High),
+        ("19th century", "1801-01-01", "1900-12-31", High),
+        ("XIX-XX вв.", "1801-01-01", "2000-12-31", High),
+        ("c. 1850", "1850-01-01", "1850-12-31", Medium),
+        ("около 1850 г.", "1850-01-01", "1850-12-31", Medium),
+        ("1850?", "1850-01-01", "1850-12-31", Medium),
+        ("начало XX века", "1901-01-01", "1934-05-02", Medium),
+        (
+            "вторая половина XIX века",
+            "1851-01-01",
+            "1900-12-31",
+            Medium,
+        ),
+        ("mid-1950s", "1953-05-02", "1956-08-31", Medium),
+        ("начало 1 мая 1953", "1953-05-01", "1953-05-01", Medium),
+        ("end of May 1, 1953", "1953-05-01", "1953-05-01", Medium),
+        ("Москва, 1953", "1953-01-01", "1953-12-31", Low),
+    ];
+    for (text, start, end, confidence) in cases {
+        assert_eq!(
+            interpret(text),
+            (start.to_string(), end.to_string(), confidence),
+            "{text}"
+        );
+    }
+    for text in ["", "давно", "XIX"] {
+        assert_eq!(DateRange::interpret(text), None, "{text}");
+    }
+}
+
+#[test]
+fn value_is_preferred_to_display_date() {
+    let date = parse_date("1953-05");
+    let range = date.range().unwrap();
+    assert_eq!(range.start, NaiveDate::from_ymd_opt(1953, 5, 1).unwrap());
+    assert_eq!(range.end, NaiveDate::from_ymd_opt(1953, 5, 31).unwrap());
+    assert_eq!(range.confidence, Confidence::High);
+
+    let date = Date {
+        lang: None,
+        iso_date: None,
+        display_date: Some("1948-53".into()),
+    };
+    assert!(date
+        .range()
+        .unwrap()
+        .contains(NaiveDate::from_ymd_opt(1950, 1, 1).unwrap()));
+}
@@ -0,0 +1,62 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use fb2::Error;
+
+const WITH_COVER: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">
+    <description>
+        <title-info>
+            <book-title>Title</book-title>
+            <coverpage><image l:href="#cover.jpg"/></coverpage>
+        </title-info>
+    </description>
+    <body>
+        <section><p>Text</p><image l:href="#inline.png"/></section>
+    </body>
+    <binary id="inline.png" content-type="image/png">iVBORw0KGgo=</binary>
+    <binary id="cover.jpg" content-type="image/jpeg">/9j/4AAQSkZJRg==</binary>
+</FictionBook>"##;
+
+#[test]
+fn read_description_matches_full_parse() {
+    let path = "tests/resources/complex.fb2";
+    let book = fb2::from_path(path).unwrap();
+    let file = BufReader::new(File::open(path).unwrap());
+    let description = fb2::read_description(file).unwrap();
+    assert_eq!(description, book.description);
+}
+
+#[test]
+fn read_description_ignores_malformed_rest() {
+    let end = WITH_COVER.find("<body>").unwrap();
+    let description = fb2::read_description(&WITH_COVER.as_bytes()[..end]).unwrap();
+    assert_eq!(description.title_info.book_title.value, "Title");
+}
+
+#[test]
+fn read_description_with_cover_finds_binary() {
+    let (description, cover) = fb2::read_description_with_cover(WITH_COVER.as_bytes()).unwrap();
+    assert_eq!(description.title_info.book_title.value, "Title");
+    let cover = cover.unwrap();
+    assert_eq!(cover.id, "cover.jpg");
+    assert_eq!(cover.content_type, "image/jpeg");
+    assert_eq!(cover.content, "/9j/4AAQSkZJRg==");
+}
+
+#[test]
+fn read_description_with_missing_cover_binary() {
+    let end = WITH_COVER.find("<binary id=\"cover.jpg\"").unwrap();
+    let content = format!("{}</FictionBook>", &WITH_COVER[..end]);
+    let (_, cover) = fb2::read_description_with_cover(content.as_bytes()).unwrap();
+    assert_eq!(cover, None);
+}
+
+#[test]
+fn missing_description_is_structure_error() {
+    let start = WITH_COVER.find("<description>").unwrap();
+    let end = WITH_COVER.find("<body>").unwrap();
+    let content = format!("{}{}", &WITH_COVER[..start], &WITH_COVER[end..]);
+    let error = fb2::read_description(content.as_bytes()).unwrap_err();
+    assert!(matches!(error, Error::Structure(_)), "{error:?}");
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "encoding")]
+
+use fb2::encoding::{detect, DetectedEncoding, Encoding, EncodingSource};
+
+const TITLE: &str = "Вторая мировая война";
+const ANNOTATION: &str = "Шеститомный труд У. Черчилля – героическая эпопея народов, выступивших против \
+    планетарной опасности, написанная выдающимся политиком, скрупулезным историком и талантливым литератором.";
+
+fn book(declaration: &str) -> String {
+    format!(
+        r#"{declaration}
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0">
+    <description>
+        <title-info>
+            <book-title>{TITLE}</book-title>
+            <annotation><p>{ANNOTATION}</p></annotation>
+        </title-info>
+    </description>
+    <body>
+        <section><p>{ANNOTATION}</p></section>
+    </body>
+</FictionBook>"#
+    )
+}
+
+fn encode(content: &str, label: &str) -> Vec<u8> {
+    let encoding = Encoding::for_label(label.as_bytes()).unwrap();
+    let (bytes, _, _) = encoding.encode(content);
+    bytes.into_owned()
+}
+
+fn parse(bytes: &[u8]) -> DetectedEncoding {
+    let (book, encoding) = fb2::from_reader_with_encoding(bytes).unwrap();
+    assert_eq!(book.description.title_info.book_title.value, TITLE);
+    encoding
+}
+
+#[test]
+fn declared_windows_1251() {
+    let bytes = encode(
+        &book(r#"<?xml version="1.0" encoding="windows-1251"?>"#),
+        "windows-1251",
+    );
+    let encoding = parse(&bytes);
+    assert_eq!(encoding.encoding.name(), "windows-1251");
+    assert_eq!(encoding.source, EncodingSource::Declaration);
+}
+
+#[test]
+fn missing_declaration_in_koi8_r() {
+    let bytes = encode(&book(""), "koi8-r");
+    let encoding = parse(&bytes);
+    assert_eq!(encoding.encoding.name(), "KOI8-R");
+    assert_eq!(encoding.source, EncodingSource::Guess);
+}
+
+#[test]
+fn wrong_declaration_in_cp866() {
+    let bytes = encode(&book(r#"<?xml version="1.0" encoding="UTF-8"?>"#), "ibm866");
+    let encoding = parse(&bytes);
+    assert_eq!(encoding.encoding.name(), "IBM866");
+    assert_eq!(encoding.source, EncodingSource::Guess);
+}
+
+#[test]
+fn wrong_legacy_declaration() {
+    let bytes = encode(
+        &book(r#"<?xml version="1.0" encoding="koi8-r"?>"#),
+        "windows-1251",
+    );
+    let encoding = parse(&bytes);
+    assert_eq!(encoding.encoding.name(), "windows-1251");
+    assert_eq!(encoding.source, EncodingSource::Guess);
+}
+
+#[test]
+fn utf8_declared_as_windows_1251() {
+    let bytes = book(r#"<?xml version="1.0" encoding="windows-1251"?>"#).into_bytes();
+    let encoding = parse(&bytes);
+    assert_eq!(encoding.encoding.name(), "UTF-8");
+    assert_eq!(encoding.source, EncodingSource::Guess);
+}
+
+#[test]
+fn utf8_without_declaration() {
+    let bytes = book("").into_bytes();
+    let encoding = parse(&bytes);
+    assert_eq!(encoding.encoding.name(), "UTF-8");
+    assert_eq!(encoding.source, EncodingSource::Default);
+}
+
+#[test]
+fn utf16_with_bom() {
+    let content = book(r#"<?xml version="1.0" encoding="UTF-16"?>"#);
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend(content.encode_utf16().flat_map(u16::to_le_bytes));
+    let encoding = parse(&bytes);
+    assert_eq!(encoding.encoding.name(), "UTF-16LE");
+    assert_eq!(encoding.source, EncodingSource::ByteOrderMark);
+}
+
+#[test]
+fn detect_without_parsing() {
+    let bytes = encode(&book(""), "windows-1251");
+    assert_eq!(detect(&bytes).encoding.name(), "windows-1251");
+}
+
+#[test]
+fn read_description_in_windows_1251() {
+    let bytes = encode(
+        &book(r#"<?xml version="1.0" encoding="windows-1251"?>"#),
+        "windows-1251",
+    );
+    let description = fb2::read_description(bytes.as_slice()).unwrap();
+    assert_eq!(description.title_info.book_title.value, TITLE);
+}
+
+#[test]
+fn read_description_in_utf_16() {
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend(book("").encode_utf16().flat_map(u16::to_le_bytes));
+    let description = fb2::read_description(bytes.as_slice()).unwrap();
+    assert_eq!(description.title_info.book_title.value, TITLE);
+}
+
+#[test]
+fn write_in_legacy_and_utf16_encodings() {
+    let book = fb2::from_str(&book("")).unwrap();
+    for label in ["windows-1251", "koi8-r", "utf-16le", "utf-16be"] {
+        let encoding = Encoding::for_label(label.as_bytes()).unwrap();
+        let options = fb2::WriteOptions::default().encoding(encoding);
+        let mut bytes = vec![];
+        fb2::to_writer_with_options(&book, &mut bytes, &options).unwrap();
+        let (read_back, encoding) = fb2::from_reader_with_encoding(bytes.as_slice()).unwrap();
+        assert_eq!(encoding.encoding, options.encoding, "{label}");
+        assert_ne!(encoding.source, EncodingSource::Guess, "{label}");
+        assert_eq!(read_back, book, "{label}");
+    }
+
+    // characters missing in the encoding are written as references
+    let mut book = book;
+    book.description.title_info.book_title.value = "Ǆ".into();
+    let options =
+        fb2::WriteOptions::default().encoding(Encoding::for_label(b"windows-1251").unwrap());
+    let mut bytes = vec![];
+    fb2::to_writer_with_options(&book, &mut bytes, &options).unwrap();
+    let (read_back, _) = fb2::from_reader_with_encoding(bytes.as_slice()).unwrap();
+    assert_eq!(read_back.description.title_info.book_title.value, "Ǆ");
+}
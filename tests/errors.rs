@@ -0,0 +1,78 @@
+use fb2::Error;
+
+const MINIMAL: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0">
+    <description>
+        <title-info>
+            <book-title>Title</book-title>
+        </title-info>
+    </description>
+    <body>
+        <section><p>Text</p></section>
+    </body>
+</FictionBook>"#;
+
+#[test]
+fn parse_from_str() {
+    let book = fb2::from_str(MINIMAL).unwrap();
+    assert_eq!(book.description.title_info.book_title.value, "Title");
+}
+
+#[test]
+fn parse_from_reader() {
+    let book = fb2::from_reader(MINIMAL.as_bytes()).unwrap();
+    assert_eq!(book.bodies.len(), 1);
+}
+
+#[test]
+fn parse_from_path() {
+    let path = "tests/resources/minimal.fb2";
+    let content = std::fs::read_to_string(path).unwrap();
+    let expected: fb2::FictionBook = quick_xml::de::from_str(&content).unwrap();
+    assert_eq!(fb2::from_path(path).unwrap(), expected);
+}
+
+#[test]
+fn malformed_xml_is_syntax_error() {
+    let content = MINIMAL.replace("</title-info>", "");
+    let error = fb2::from_str(&content).unwrap_err();
+    let Error::Syntax(syntax) = &error else {
+        panic!("{error:?}");
+    };
+    assert!(!syntax.message().is_empty());
+    assert!(syntax.location.is_some());
+    assert!(std::error::Error::source(&error).is_some());
+}
+
+#[test]
+fn truncated_xml_is_syntax_error() {
+    let error = fb2::from_str(&MINIMAL[..MINIMAL.len() / 2]).unwrap_err();
+    assert!(matches!(error, Error::Syntax(_)), "{error:?}");
+}
+
+#[test]
+fn missing_element_is_structure_error() {
+    let content = MINIMAL.replace("<book-title>Title</book-title>", "");
+    let error = fb2::from_str(&content).unwrap_err();
+    assert!(matches!(error, Error::Structure(_)), "{error:?}");
+}
+
+#[test]
+#[cfg(not(feature = "encoding"))]
+fn invalid_utf8_is_encoding_error() {
+    let (head, tail) = MINIMAL.split_once("Text").unwrap();
+    let content = [
+        head.as_bytes(),
+        &[0xD2, 0xE5, 0xEA, 0xF1, 0xF2],
+        tail.as_bytes(),
+    ]
+    .concat();
+    let error = fb2::from_reader(content.as_slice()).unwrap_err();
+    assert!(matches!(error, Error::Encoding(_)), "{error:?}");
+}
+
+#[test]
+fn missing_file_is_io_error() {
+    let error = fb2::from_path("tests/resources/missing.fb2").unwrap_err();
+    assert!(matches!(error, Error::Io(_)), "{error:?}");
+}
@@ -0,0 +1,293 @@
+use fb2::{Error, ExtraElement, ExtraNode, Extras, RepairAction, StyleElement};
+
+const BOOK: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink" xmlns:v="urn:vendor">
+    <description>
+        <title-info>
+            <book-title>Title</book-title>
+            <lang>en</lang>
+            <v:rating stars="5"/>
+        </title-info>
+        <v:shelf>
+            <v:name>Favourites &amp; more</v:name>
+        </v:shelf>
+    </description>
+    <body>
+        <section v:rank="1">
+            <p xml:space="preserve">Text<br/> more <v:note kind="a">Note <b>bold</b></v:note>.</p>
+            <v:marker/>
+            <p>Next</p>
+        </section>
+    </body>
+    <v:index/>
+</FictionBook>"#;
+
+fn element(name: &str, attributes: &[(&str, &str)], children: Vec<ExtraNode>) -> ExtraElement {
+    ExtraElement {
+        name: name.into(),
+        attributes: attributes
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+        children,
+    }
+}
+
+fn text(text: &str) -> ExtraNode {
+    ExtraNode::Text(text.into())
+}
+
+#[test]
+fn unknown_nodes_are_kept() {
+    let (book, repairs) = fb2::from_str_with_repairs(BOOK).unwrap();
+    assert!(
+        repairs
+            .iter()
+            .all(|repair| repair.action == RepairAction::Kept),
+        "{repairs:?}"
+    );
+    let paths: Vec<_> = repairs
+        .iter()
+        .map(|repair| repair.location.path.as_str())
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            "/FictionBook/description/title-info/rating",
+            "/FictionBook/description/shelf",
+            "/FictionBook/body/section/@v:rank",
+            "/FictionBook/body/section/p[1]/@xml:space",
+            "/FictionBook/body/section/p[1]/br",
+            "/FictionBook/body/section/p[1]/note",
+            "/FictionBook/body/section/marker",
+            "/FictionBook/index",
+        ]
+    );
+    assert_eq!(
+        book.extras,
+        Extras {
+            attributes: vec![("xmlns:v".into(), "urn:vendor".into())],
+            elements: vec![element("v:index", &[], vec![])],
+            misc: vec![],
+        }
+    );
+    let description = &book.description;
+    assert_eq!(
+        description.extras.elements,
+        [element(
+            "v:shelf",
+            &[],
+            vec![
+                text("\n            "),
+                ExtraNode::Element(element("v:name", &[], vec![text("Favourites & more")])),
+                text("\n        "),
+            ]
+        )]
+    );
+    assert_eq!(
+        description.title_info.extras.elements,
+        [element("v:rating", &[("stars", "5")], vec![])]
+    );
+
+    let section = &book.bodies[0].sections[0];
+    assert_eq!(section.extras.attributes, [("v:rank".into(), "1".into())]);
+    assert_eq!(section.extras.elements, [element("v:marker", &[], vec![])]);
+    let content = &section.content.as_ref().unwrap().content;
+    assert_eq!(content.len(), 2);
+    let fb2::SectionPart::Paragraph(paragraph) = &content[0] else {
+        panic!("{content:?}");
+    };
+    assert_eq!(
+        paragraph.elements,
+        [StyleElement::Text("Text more .".into())]
+    );
+    assert_eq!(
+        paragraph.extras,
+        Extras {
+            attributes: vec![("xml:space".into(), "preserve".into())],
+            elements: vec![
+                element("br", &[], vec![]),
+                element(
+                    "v:note",
+                    &[("kind", "a")],
+                    vec![
+                        text("Note "),
+                        ExtraNode::Element(element("b", &[], vec![text("bold")]))
+                    ]
+                ),
+            ],
+            misc: vec![],
+        }
+    );
+    let fb2::SectionPart::Paragraph(paragraph) = &content[1] else {
+        panic!("{content:?}");
+    };
+    assert!(paragraph.extras.is_empty());
+}
+
+#[test]
+fn unknown_nodes_are_serialized_back() {
+    let book = fb2::from_str(BOOK).unwrap();
+    let section = &book.bodies[0].sections[0];
+    let xml = quick_xml::se::to_string_with_root("section", section).unwrap();
+    assert_eq!(
+        xml,
+        r#"<section v:rank="1"><p xml:space="preserve">Text more .<br/><v:note kind="a">Note <b>bold</b></v:note></p><p>Next</p><v:marker/></section>"#
+    );
+
+    let xml = quick_xml::se::to_string_with_root("FictionBook", &book).unwrap();
+    assert!(
+        xml.starts_with(r#"<FictionBook xmlns:v="urn:vendor">"#),
+        "{xml}"
+    );
+    assert!(
+        xml.contains("<v:rating stars=\"5\"/></title-info>"),
+        "{xml}"
+    );
+    assert!(xml.ends_with("<v:index/></FictionBook>"), "{xml}");
+    let read_back = fb2::from_str(&xml).unwrap();
+    assert_eq!(read_back.extras, book.extras);
+    assert_eq!(read_back.description.extras, book.description.extras);
+    assert_eq!(read_back.bodies[0].sections[0].extras, section.extras);
+}
+
+#[test]
+fn hidden_elements_keep_the_locations() {
+    let content = r#"<FictionBook>
+    <description><title-info><book-title>Title</book-title></title-info></description>
+    <body>
+        <section>
+            <p><v:note>
+            </v:note><strong>a</strong></p>
+            <strong>b</strong>
+        </section>
+    </body>
+</FictionBook>"#;
+    let (_, repairs) = fb2::from_str_with_repairs(content).unwrap();
+    assert_eq!(
+        repairs[0].to_string(),
+        "5:16 /FictionBook/body/section/p/note: unknown `note` kept"
+    );
+    assert_eq!(
+        repairs[1].to_string(),
+        "7:13 /FictionBook/body/section/strong: `strong` wrapped into `p`"
+    );
+}
+
+fn section(content: &str) -> String {
+    format!(
+        r#"<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:foo="urn:foo">
+    <description><title-info><book-title>Title</book-title></title-info></description>
+    <body><section>{content}</section></body>
+</FictionBook>"#
+    )
+}
+
+#[test]
+fn hidden_elements_are_not_counted_among_the_children() {
+    let content = section("<p>a</p><foo:bar>ext</foo:bar><p>b</p><strong>s</strong>");
+    let (_, repairs) = fb2::from_str_with_repairs(&content).unwrap();
+    let repairs: Vec<_> = repairs.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        repairs,
+        [
+            "3:28 /FictionBook/body/section/bar: unknown `bar` kept",
+            "3:58 /FictionBook/body/section/strong: `strong` wrapped into `p`",
+        ]
+    );
+}
+
+#[test]
+fn extras_of_skipped_owners_are_dropped() {
+    let content = section(
+        r#"<table><tr><td>cell</td></tr><p data-y="2">stray</p></table><p data-x="1">text</p>"#,
+    );
+    let book = fb2::from_str(&content).unwrap();
+    let content = &book.bodies[0].sections[0].content.as_ref().unwrap().content;
+    let fb2::SectionPart::Paragraph(paragraph) = &content[1] else {
+        panic!("{content:?}");
+    };
+    assert_eq!(
+        paragraph.extras.attributes,
+        [("data-x".to_string(), "1".to_string())]
+    );
+}
+
+#[test]
+fn unknown_elements_in_known_children_go_to_the_nearest_owner() {
+    let content = section(
+        r#"<title><p data-t="3">T<foo:bar/></p></title><poem><stanza><v>line<foo:baz/></v></stanza></poem><p data-x="1">text</p>"#,
+    );
+    let book = fb2::from_str(&content).unwrap();
+    let section = &book.bodies[0].sections[0];
+    assert_eq!(section.extras, Extras::default());
+    let content = section.content.as_ref().unwrap();
+    let fb2::TitleElement::Paragraph(title) = &content.title.as_ref().unwrap().elements[0] else {
+        panic!("{content:?}");
+    };
+    assert_eq!(
+        title.extras.attributes,
+        [("data-t".to_string(), "3".to_string())]
+    );
+    assert_eq!(title.extras.elements, [element("foo:bar", &[], vec![])]);
+    let [fb2::SectionPart::Poem(poem), fb2::SectionPart::Paragraph(paragraph)] =
+        content.content.as_slice()
+    else {
+        panic!("{content:?}");
+    };
+    let fb2::PoemStanza::Stanza(stanza) = &poem.stanzas[0] else {
+        panic!("{poem:?}");
+    };
+    assert_eq!(
+        stanza.lines[0].extras.elements,
+        [element("foo:baz", &[], vec![])]
+    );
+    assert_eq!(
+        paragraph.extras.attributes,
+        [("data-x".to_string(), "1".to_string())]
+    );
+}
+
+#[test]
+fn owners_in_skipped_unknown_elements_are_not_taken() {
+    let content = r#"<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:foo="urn:foo">
+    <description><title-info><book-title>Title</book-title><foo:wrap><p data-y="2">stray</p></foo:wrap><annotation><p data-x="1">text</p></annotation></title-info></description>
+    <body><section><p>a</p></section></body>
+</FictionBook>"#;
+    let book = fb2::from_str(content).unwrap();
+    let title_info = &book.description.title_info;
+    let stray = element("p", &[("data-y", "2")], vec![text("stray")]);
+    assert_eq!(
+        title_info.extras.elements,
+        [element("foo:wrap", &[], vec![ExtraNode::Element(stray)])]
+    );
+    let fb2::AnnotationElement::Paragraph(paragraph) =
+        &title_info.annotation.as_ref().unwrap().elements[0]
+    else {
+        panic!("{:?}", title_info.annotation);
+    };
+    assert_eq!(
+        paragraph.extras.attributes,
+        [("data-x".to_string(), "1".to_string())]
+    );
+}
+
+#[test]
+fn strict_parsing_fails_on_unknown_nodes() {
+    let content = section("<p>a</p><foo:bar>ext</foo:bar>");
+    let error = fb2::from_str_strict(&content).unwrap_err();
+    let Error::NotConforming(repair) = &error else {
+        panic!("{error:?}");
+    };
+    assert_eq!(repair.action, RepairAction::Kept);
+    assert_eq!(repair.location.path, "/FictionBook/body/section/bar");
+    assert!(fb2::from_reader_strict(content.as_bytes()).is_err());
+
+    let content = section(r#"<p foo:align="left">a</p>"#);
+    let error = fb2::from_str_strict(&content).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "3:23 /FictionBook/body/section/p/@foo:align: `@foo:align` is not allowed in its place"
+    );
+    assert!(fb2::from_str_strict(&section("<p>a</p>")).is_ok());
+}
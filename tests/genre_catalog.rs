@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+
+use fb2::{Genre, GenreGroup, Locale};
+
+#[test]
+fn genre_has_names_and_group() {
+    let genre = Genre::SfHorror;
+    assert_eq!(genre.code(), "sf_horror");
+    assert_eq!(genre.name(Locale::English), Some("Horror"));
+    assert_eq!(genre.name(Locale::Russian), Some("Ужасы"));
+    assert_eq!(genre.group(), Some(GenreGroup::ScienceFiction));
+    assert_eq!(
+        GenreGroup::ScienceFiction.name(Locale::English),
+        "Science Fiction & Fantasy"
+    );
+    assert_eq!(
+        GenreGroup::ScienceFiction.name(Locale::Russian),
+        "Фантастика"
+    );
+}
+
+#[test]
+fn unknown_genres_are_not_in_catalog() {
+    for genre in [Genre::Unrecognised, Genre::Other("sf_litrpg".into())] {
+        assert_eq!(genre.name(Locale::English), None);
+        assert_eq!(genre.group(), None);
+        assert!(!Genre::known().any(|known| *known == genre));
+    }
+}
+
+#[test]
+fn every_known_genre_is_complete() {
+    let mut codes = HashSet::new();
+    for genre in Genre::known() {
+        assert!(codes.insert(genre.code()), "{genre:?}");
+        assert_eq!(&Genre::from(genre.code().to_string()), genre);
+        assert!(genre.name(Locale::English).is_some(), "{genre:?}");
+        assert!(genre.name(Locale::Russian).is_some(), "{genre:?}");
+        assert!(genre.group().is_some(), "{genre:?}");
+    }
+    assert!(codes.len() > 200);
+}
+
+#[test]
+fn groups_cover_every_known_genre() {
+    let grouped: usize = GenreGroup::ALL
+        .iter()
+        .map(|group| {
+            let genres = group.genres().count();
+            assert!(genres > 0, "{group:?}");
+            genres
+        })
+        .sum();
+    let canonical = Genre::known().filter(|genre| genre.is_canonical()).count();
+    assert_eq!(grouped, canonical);
+    assert!(GenreGroup::Detective
+        .genres()
+        .any(|genre| *genre == Genre::DetPolice));
+}
+
+#[test]
+fn aliases_have_one_canonical_genre() {
+    assert_eq!(Genre::SfCyberPunk.canonical(), Genre::SfCyberpunk);
+    assert!(!Genre::SfCyberPunk.is_canonical());
+    assert!(Genre::SfCyberpunk.is_canonical());
+    assert_eq!(Genre::RomanceSf.canonical(), Genre::LoveSf);
+    for genre in Genre::known() {
+        assert!(genre.canonical().is_canonical(), "{genre:?}");
+    }
+}
+
+#[test]
+fn legacy_codes_are_normalized() {
+    let canonical = |code: &str| Genre::Other(code.into()).canonical();
+    assert_eq!(canonical("romance"), Genre::Love);
+    assert_eq!(canonical("literature_rus_classsic"), Genre::ProseRusClassic);
+    assert_eq!(canonical(" SF-History "), Genre::SfHistory);
+    assert_eq!(canonical("SF_CYBER_PUNK"), Genre::SfCyberpunk);
+    assert_eq!(canonical("sf_litrpg"), Genre::Other("sf_litrpg".into()));
+}
+
+#[test]
+fn original_code_is_kept() {
+    let content = r#"<FictionBook>
+<description><title-info>
+    <genre>sf_cyber_punk</genre>
+    <genre>Romance</genre>
+    <book-title>Title</book-title>
+</title-info></description>
+<body><section><p>Text</p></section></body>
+</FictionBook>"#;
+    let book = fb2::from_str(content).unwrap();
+    let genres = &book.description.title_info.genres;
+    assert_eq!(genres[0].value.code(), "sf_cyber_punk");
+    assert_eq!(genres[0].value.canonical(), Genre::SfCyberpunk);
+    assert_eq!(genres[1].value.code(), "Romance");
+    assert_eq!(genres[1].value.canonical(), Genre::Love);
+}
@@ -0,0 +1,357 @@
+use fb2::ImageRef;
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1A\n";
+const JPEG: &[u8] = b"\xFF\xD8\xFF\xE0\x00\x10JFIF";
+
+const BOOK: &str = r##"<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">
+    <description>
+        <title-info>
+            <book-title>Title</book-title>
+            <annotation><p>See <image l:href="#annotation.png"/></p></annotation>
+            <coverpage><image l:href="#cover.jpg"/></coverpage>
+        </title-info>
+    </description>
+    <body>
+        <image l:href="Body.JPG"/>
+        <section>
+            <image l:href="#%D0%BE%D0%B1%D0%BB.png"/>
+            <p>A <a l:href="#note"><image l:href="#in-link.png"/></a> link</p>
+            <table><tr><td><image l:href="#missing.png"/></td></tr></table>
+            <image/>
+        </section>
+    </body>
+    <binary id="cover.jpg" content-type="image/jpeg">AAAA</binary>
+    <binary id="annotation.png" content-type="image/png">AAAA</binary>
+    <binary id="body.jpg" content-type="image/jpeg">AAAA</binary>
+    <binary id="обл.png" content-type="image/png">AAAA</binary>
+    <binary id="in-link.png" content-type="image/png">AAAA</binary>
+</FictionBook>"##;
+
+#[test]
+fn images_in_document_order() {
+    let book = fb2::from_str(BOOK).unwrap();
+    let hrefs: Vec<_> = book.images().iter().map(ImageRef::href).collect();
+    assert_eq!(
+        hrefs,
+        [
+            Some("#annotation.png"),
+            Some("#cover.jpg"),
+            Some("Body.JPG"),
+            Some("#%D0%BE%D0%B1%D0%BB.png"),
+            Some("#in-link.png"),
+            Some("#missing.png"),
+            None,
+        ]
+    );
+    assert!(matches!(book.images()[1], ImageRef::Inline(_)));
+    assert!(matches!(book.images()[2], ImageRef::Image(_)));
+}
+
+#[test]
+fn resolve_sloppy_links() {
+    let book = fb2::from_str(BOOK).unwrap();
+    let ids: Vec<_> = book
+        .resolve_images()
+        .iter()
+        .map(|resolved| resolved.binary.map(|binary| binary.id.as_str()))
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            Some("annotation.png"),
+            Some("cover.jpg"),
+            Some("body.jpg"),
+            Some("обл.png"),
+            Some("in-link.png"),
+            None,
+            None,
+        ]
+    );
+}
+
+#[test]
+fn dangling_images() {
+    let book = fb2::from_str(BOOK).unwrap();
+    let hrefs: Vec<_> = book.dangling_images().iter().map(ImageRef::href).collect();
+    assert_eq!(hrefs, [Some("#missing.png"), None]);
+}
+
+#[test]
+fn exact_id_is_preferred() {
+    let mut book = fb2::from_str(BOOK).unwrap();
+    let mut upper = book.binaries[2].clone();
+    upper.id = "Body.JPG".into();
+    book.binaries.push(upper);
+    assert_eq!(book.find_binary("Body.JPG").unwrap().id, "Body.JPG");
+    assert_eq!(book.find_binary("#BODY.jpg").unwrap().id, "body.jpg");
+    assert_eq!(book.find_binary("cover.jpg").unwrap().id, "cover.jpg");
+    assert!(book.find_binary("#cover").is_none());
+}
+
+#[test]
+fn add_binary_with_unique_id() {
+    let mut book = fb2::from_str(BOOK).unwrap();
+    assert_eq!(book.add_binary("new.png", PNG), "new.png");
+    assert_eq!(book.add_binary("Cover.jpg", JPEG), "Cover-2.jpg");
+    assert_eq!(book.add_binary("cover.jpg", JPEG), "cover-3.jpg");
+    assert_eq!(book.add_binary(" my image ", b"data"), "my_image");
+    assert_eq!(book.add_binary("", PNG), "image");
+
+    let added = book
+        .binaries
+        .iter()
+        .find(|b| b.id == "Cover-2.jpg")
+        .unwrap();
+    assert_eq!(added.content_type, "image/jpeg");
+    assert_eq!(added.content, "/9j/4AAQSkZJRg==");
+    assert_eq!(added.decode().unwrap(), JPEG);
+    let unknown = book.binaries.iter().find(|b| b.id == "my_image").unwrap();
+    assert_eq!(unknown.content_type, "application/octet-stream");
+    // the sloppy link still finds the old binary
+    assert_eq!(book.find_binary("#Cover.jpg").unwrap().id, "cover.jpg");
+
+    // the given content type wins over the sniffed one
+    let id = book.add_binary_with_content_type("notes.pdf", b"%PDF-1.4", "application/pdf");
+    assert_eq!(
+        book.find_binary(&id).unwrap().content_type,
+        "application/pdf"
+    );
+    let id = book.add_binary_with_content_type("photo", JPEG, "image/pjpeg");
+    assert_eq!(book.find_binary(&id).unwrap().content_type, "image/pjpeg");
+}
+
+#[test]
+fn replace_and_remove_binary() {
+    let mut book = fb2::from_str(BOOK).unwrap();
+    let replaced = book.replace_binary("cover.jpg", PNG).unwrap();
+    assert_eq!(replaced.content, "AAAA");
+    let cover = book.find_binary("#cover.jpg").unwrap();
+    assert_eq!(cover.content_type, "image/png");
+    assert_eq!(cover.decode().unwrap(), PNG);
+    assert!(book.replace_binary("missing.png", PNG).is_none());
+
+    let replaced = book.replace_binary_with_content_type("body.jpg", b"%PDF", "application/pdf");
+    assert_eq!(replaced.unwrap().content_type, "image/jpeg");
+    assert_eq!(book.binaries[2].content_type, "application/pdf");
+
+    let count = book.binaries.len();
+    assert_eq!(book.remove_binary("cover.jpg").unwrap().id, "cover.jpg");
+    assert_eq!(book.binaries.len(), count - 1);
+    assert!(book.remove_binary("cover.jpg").is_none());
+    assert_eq!(book.dangling_images().len(), 3);
+}
+
+#[test]
+fn rename_binary_and_links() {
+    let mut book = fb2::from_str(BOOK).unwrap();
+    let id = book.rename_binary("body.jpg", "cover.jpg", true).unwrap();
+    assert_eq!(id, "cover-2.jpg");
+    assert_eq!(book.images()[2].href(), Some("#cover-2.jpg"));
+    assert_eq!(book.images()[1].href(), Some("#cover.jpg"));
+    assert_eq!(book.dangling_images().len(), 2);
+
+    let id = book
+        .rename_binary("cover-2.jpg", "Cover-2.jpg", false)
+        .unwrap();
+    assert_eq!(id, "Cover-2.jpg");
+    assert_eq!(book.images()[2].href(), Some("#cover-2.jpg"));
+    assert!(book.rename_binary("body.jpg", "other.jpg", true).is_none());
+}
+
+#[test]
+fn renamed_links_are_written() {
+    let mut book = fb2::from_str(BOOK).unwrap();
+    book.rename_binary("in-link.png", "link.png", true);
+    let written = fb2::to_string(&book).unwrap();
+    assert!(
+        written.contains(r##"<image l:href="#link.png"/>"##),
+        "{written}"
+    );
+    assert!(written.contains(r#"<binary id="link.png""#), "{written}");
+}
+
+const WITH_ORPHANS: &str = r##"<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">
+    <description>
+        <title-info><book-title>Title</book-title></title-info>
+        <src-title-info>
+            <book-title>Original</book-title>
+            <coverpage><image l:href="#original.jpg"/></coverpage>
+        </src-title-info>
+        <document-info>
+            <history><p><image l:href="#HISTORY.png"/></p></history>
+        </document-info>
+    </description>
+    <body>
+        <section>
+            <p><a l:href="#attachment.pdf">attachment</a> <a l:href="unused.jpg">site</a></p>
+            <poem><stanza><v>A <image l:href="#verse.gif"/></v></stanza></poem>
+        </section>
+    </body>
+    <binary id="unused.jpg" content-type="image/jpeg">AAAAAAAA</binary>
+    <binary id="original.jpg" content-type="image/jpeg">AAAA</binary>
+    <binary id="history.png" content-type="image/png">AAAA</binary>
+    <binary id="attachment.pdf" content-type="application/pdf">AAAA</binary>
+    <binary id="verse.gif" content-type="image/gif">AAAA</binary>
+</FictionBook>"##;
+
+#[test]
+fn orphan_binaries() {
+    let book = fb2::from_str(WITH_ORPHANS).unwrap();
+    let ids: Vec<_> = book
+        .orphan_binaries()
+        .iter()
+        .map(|binary| binary.id.as_str())
+        .collect();
+    assert_eq!(ids, ["unused.jpg"]);
+    assert!(fb2::from_str(BOOK).unwrap().orphan_binaries().is_empty());
+}
+
+#[test]
+fn remove_orphan_binaries() {
+    let mut book = fb2::from_str(WITH_ORPHANS).unwrap();
+    let removed = book.remove_orphan_binaries();
+    assert_eq!(removed.binaries.len(), 1);
+    assert_eq!(removed.encoded_bytes, 8);
+    let ids: Vec<_> = book
+        .binaries
+        .iter()
+        .map(|binary| binary.id.as_str())
+        .collect();
+    assert_eq!(
+        ids,
+        ["original.jpg", "history.png", "attachment.pdf", "verse.gif"]
+    );
+    assert_eq!(book.remove_orphan_binaries(), Default::default());
+}
+
+fn with_covers(covers: &str, body_image: &str) -> String {
+    with_images(covers, body_image, "")
+}
+
+fn with_images(covers: &str, body_image: &str, notes_image: &str) -> String {
+    format!(
+        r##"<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">
+    <description>
+        <title-info><book-title>Title</book-title>{covers}</title-info>
+    </description>
+    <body>{body_image}<title><p>Title</p></title><section><p>Text</p></section></body>
+    <body name="notes">{notes_image}<section><p>Note</p></section></body>
+    <binary id="png.jpg" content-type="image/jpeg">iVBORw0KGgo=</binary>
+    <binary id="cover.jpg" content-type="image/jpeg">/9j/4AAQSkZJRg==</binary>
+    <binary id="unknown.bin" content-type="application/x-custom">AAAA</binary>
+</FictionBook>"##
+    )
+}
+
+#[test]
+fn cover_of_cover_page() {
+    let content = with_covers(
+        r##"<coverpage><image l:href="#missing.jpg"/><image l:href="Cover.jpg"/></coverpage>"##,
+        r##"<image l:href="#png.jpg"/>"##,
+    );
+    let book = fb2::from_str(&content).unwrap();
+    assert_eq!(book.cover_binary().unwrap().id, "cover.jpg");
+    let cover = book.cover().unwrap().unwrap();
+    assert_eq!(cover.content, JPEG);
+    assert_eq!(cover.content_type, "image/jpeg");
+}
+
+#[test]
+fn cover_falls_back_to_body_image() {
+    let content = with_covers("", r##"<image l:href="#png.jpg"/>"##);
+    let book = fb2::from_str(&content).unwrap();
+    let cover = book.cover().unwrap().unwrap();
+    assert_eq!(cover.content, PNG);
+    // the content type of the content rather than the declared one
+    assert_eq!(cover.content_type, "image/png");
+
+    let content = with_covers("", r##"<image l:href="#unknown.bin"/>"##);
+    let cover = fb2::from_str(&content).unwrap().cover().unwrap().unwrap();
+    assert_eq!(cover.content_type, "application/x-custom");
+
+    let content = with_covers("", "");
+    assert_eq!(fb2::from_str(&content).unwrap().cover().unwrap(), None);
+}
+
+#[test]
+fn read_cover_without_the_book() {
+    for (covers, body_image, expected) in [
+        (
+            r##"<coverpage><image l:href="#cover.jpg"/></coverpage>"##,
+            "",
+            Some(JPEG),
+        ),
+        (
+            r##"<coverpage><image l:href="#COVER.JPG"/></coverpage>"##,
+            "",
+            Some(JPEG),
+        ),
+        ("", r##"<image l:href="#png.jpg"/>"##, Some(PNG)),
+        ("", "<image/>", None),
+        ("", "", None),
+    ] {
+        let content = with_covers(covers, body_image);
+        let cover = fb2::read_cover(content.as_bytes()).unwrap();
+        assert_eq!(
+            cover.as_ref().map(|cover| cover.content.as_slice()),
+            expected,
+            "{covers} {body_image}"
+        );
+        let book = fb2::from_str(&content).unwrap();
+        assert_eq!(cover, book.cover().unwrap());
+    }
+}
+
+#[test]
+fn cover_is_not_an_image_of_a_later_body() {
+    let content = with_images("", "", r##"<image l:href="#cover.jpg"/>"##);
+    let book = fb2::from_str(&content).unwrap();
+    assert_eq!(book.cover_binary(), None);
+    assert_eq!(book.cover().unwrap(), None);
+    assert_eq!(fb2::read_cover(content.as_bytes()).unwrap(), None);
+}
+
+#[test]
+fn read_cover_is_the_book_cover() {
+    for (covers, body_image) in [
+        (
+            r##"<coverpage><image l:href="#missing.jpg"/><image l:href="#cover.jpg"/></coverpage>"##,
+            r##"<image l:href="#png.jpg"/>"##,
+        ),
+        (
+            r##"<coverpage><image l:href="#missing.jpg"/><image l:href="Cover.jpg"/></coverpage>"##,
+            r##"<image l:href="#png.jpg"/>"##,
+        ),
+        (
+            r##"<coverpage><image l:href="#missing.jpg"/></coverpage>"##,
+            r##"<image l:href="#png.jpg"/>"##,
+        ),
+        (
+            r##"<coverpage><image l:href="#png.JPG"/><image l:href="#cover.jpg"/></coverpage>"##,
+            "",
+        ),
+        (
+            r##"<coverpage><image/><image l:href="#missing.jpg"/></coverpage>"##,
+            "",
+        ),
+        (
+            "",
+            r##"<epigraph><p>Text</p></epigraph><image l:href="#png.jpg"/>"##,
+        ),
+        (
+            "",
+            r##"<title><p>Title</p></title><image l:href="#png.jpg"/>"##,
+        ),
+        (
+            "",
+            r##"<title><p>Title</p></title><title><p>Title</p></title><image l:href="#png.jpg"/>"##,
+        ),
+        ("", r##"<p>Text</p><image l:href="#png.jpg"/>"##),
+        ("", r##"Text<image l:href="#png.jpg"/>"##),
+    ] {
+        let content = with_covers(covers, body_image);
+        let book = fb2::from_str(&content).unwrap();
+        let cover = fb2::read_cover(content.as_bytes()).unwrap();
+        assert_eq!(cover, book.cover().unwrap(), "{covers} {body_image}");
+    }
+}
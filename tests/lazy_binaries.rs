@@ -0,0 +1,132 @@
+use std::io::Cursor;
+
+use fb2::Error;
+
+const WITH_BINARIES: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">
+    <description>
+        <title-info>
+            <book-title>Title</book-title>
+            <coverpage><image l:href="#cover.jpg"/></coverpage>
+        </title-info>
+    </description>
+    <body>
+        <section><p>Text</p><image l:href="#inline.png"/></section>
+    </body>
+    <binary id="cover.jpg" content-type="image/jpeg">/9j/4AAQ
+        SkZJRg==
+    </binary>
+    <binary id="inline.png" content-type="image/png">iVBORw0KGgo</binary>
+    <binary id="empty.gif" content-type="image/gif"/>
+</FictionBook>"##;
+
+const JPEG: &[u8] = b"\xFF\xD8\xFF\xE0\x00\x10JFIF";
+const PNG: &[u8] = b"\x89PNG\r\n\x1A\n";
+
+#[test]
+fn binaries_are_located() {
+    let (book, binaries) = fb2::from_reader_lazy(WITH_BINARIES.as_bytes()).unwrap();
+    assert_eq!(book.description.title_info.book_title.value, "Title");
+    assert_eq!(book.bodies.len(), 1);
+    assert!(book.binaries.is_empty());
+
+    let ids: Vec<_> = binaries.iter().map(|binary| binary.id.as_str()).collect();
+    assert_eq!(ids, ["cover.jpg", "inline.png", "empty.gif"]);
+    assert_eq!(binaries[1].content_type, "image/png");
+    let inline = WITH_BINARIES.find("iVBOR").unwrap() as u64;
+    assert_eq!(binaries[1].range, inline..inline + 11);
+    assert!(binaries[2].is_empty());
+}
+
+#[test]
+fn content_is_decoded_on_demand() {
+    let (_, binaries) = fb2::from_reader_lazy(WITH_BINARIES.as_bytes()).unwrap();
+    let mut source = Cursor::new(WITH_BINARIES.as_bytes());
+    assert_eq!(binaries[0].read(&mut source).unwrap(), JPEG);
+    assert_eq!(binaries[1].read(&mut source).unwrap(), PNG);
+    assert_eq!(binaries[2].read(&mut source).unwrap(), b"");
+    assert_eq!(binaries[0].decode(WITH_BINARIES.as_bytes()).unwrap(), JPEG);
+}
+
+#[test]
+fn rest_of_book_matches_full_parse() {
+    let bytes = std::fs::read("tests/resources/complex.fb2").unwrap();
+    let mut book = fb2::from_slice(&bytes).unwrap();
+    let (lazy, binaries) = fb2::from_reader_lazy(bytes.as_slice()).unwrap();
+    let expected: Vec<_> = book
+        .binaries
+        .iter()
+        .map(|binary| {
+            (
+                binary.id.as_str(),
+                binary.content_type.as_str(),
+                binary.content.as_bytes(),
+            )
+        })
+        .collect();
+    let actual: Vec<_> = binaries
+        .iter()
+        .map(|binary| {
+            let content = &bytes[binary.range.start as usize..binary.range.end as usize];
+            (binary.id.as_str(), binary.content_type.as_str(), content)
+        })
+        .collect();
+    assert_eq!(actual, expected);
+    book.binaries.clear();
+    assert_eq!(lazy, book);
+}
+
+#[test]
+fn offsets_account_for_byte_order_mark() {
+    let mut bytes = b"\xEF\xBB\xBF".to_vec();
+    bytes.extend_from_slice(WITH_BINARIES.as_bytes());
+    let (_, binaries) = fb2::from_reader_lazy(bytes.as_slice()).unwrap();
+    assert_eq!(binaries[1].decode(&bytes).unwrap(), PNG);
+}
+
+#[test]
+fn invalid_base64_is_binary_error() {
+    let content = WITH_BINARIES.replace("iVBORw0KGgo", "iVBOR*0KGgo");
+    let (_, binaries) = fb2::from_reader_lazy(content.as_bytes()).unwrap();
+    let error = binaries[1].decode(content.as_bytes()).unwrap_err();
+    assert!(matches!(error, Error::Binary(_)), "{error:?}");
+}
+
+#[test]
+fn truncated_source_is_io_error() {
+    let (_, binaries) = fb2::from_reader_lazy(WITH_BINARIES.as_bytes()).unwrap();
+    let truncated = &WITH_BINARIES.as_bytes()[..binaries[1].range.start as usize + 4];
+    let error = binaries[1].read(&mut Cursor::new(truncated)).unwrap_err();
+    assert!(matches!(error, Error::Io(_)), "{error:?}");
+    let error = binaries[1].decode(truncated).unwrap_err();
+    assert!(matches!(error, Error::Io(_)), "{error:?}");
+}
+
+#[test]
+fn missing_binary_id_is_structure_error() {
+    let content = WITH_BINARIES.replace(r#"id="inline.png" "#, "");
+    let error = fb2::from_reader_lazy(content.as_bytes()).unwrap_err();
+    assert!(matches!(error, Error::Structure(_)), "{error:?}");
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn utf_16_is_not_supported() {
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend(WITH_BINARIES.encode_utf16().flat_map(u16::to_le_bytes));
+    let error = fb2::from_reader_lazy(bytes.as_slice()).unwrap_err();
+    assert!(matches!(error, Error::Encoding(_)), "{error:?}");
+}
+
+#[test]
+fn nested_binary_is_kept_in_the_book() {
+    let content = WITH_BINARIES.replace(
+        "<section><p>Text</p>",
+        r#"<section><p>Text</p><v:binary xmlns:v="urn:v" id="nested" content-type="text/plain">AAAA</v:binary>"#,
+    );
+    let (book, binaries) = fb2::from_reader_lazy(content.as_bytes()).unwrap();
+    let ids: Vec<_> = binaries.iter().map(|binary| binary.id.as_str()).collect();
+    assert_eq!(ids, ["cover.jpg", "inline.png", "empty.gif"]);
+    let section = &book.bodies[0].sections[0];
+    assert_eq!(section.extras.elements[0].name, "v:binary");
+}
@@ -0,0 +1,123 @@
+use std::io::Cursor;
+
+use fb2::{BookItem, BookReader, Error};
+
+const BOOK: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">
+    <description>
+        <title-info>
+            <genre>sf</genre>
+            <book-title>Заголовок</book-title>
+            <sequence name="Cycle" number="1"/>
+            <sequence name="Series" number="2"/>
+        </title-info>
+    </description>
+    <body>
+        <section><p>Text</p></section>
+        <section><p>More text</p></section>
+    </body>
+    <binary id="cover.png" content-type="image/png">iVBORw0KGgo=</binary>
+</FictionBook>"##;
+
+fn location(error: &Error) -> (usize, usize, &str) {
+    let location = error.location().unwrap_or_else(|| panic!("{error:?}"));
+    (location.line, location.column, location.path.as_str())
+}
+
+#[test]
+fn invalid_attribute_is_located() {
+    let content = BOOK.replace("<book-title>", r#"<book-title xml:lang="?">"#);
+    let error = fb2::from_str(&content).unwrap_err();
+    assert!(matches!(error, Error::Structure(_)), "{error:?}");
+    assert_eq!(
+        location(&error),
+        (6, 13, "/FictionBook/description/title-info/book-title")
+    );
+    let offset = error.location().unwrap().offset as usize;
+    assert!(content[offset..].starts_with("<book-title"));
+}
+
+#[test]
+fn invalid_element_is_located() {
+    let content = BOOK.replace(
+        "<p>More text</p>",
+        r#"<table><tr><td colspan="wide">Cell</td></tr></table>"#,
+    );
+    let error = fb2::from_str(&content).unwrap_err();
+    assert_eq!(
+        location(&error),
+        (13, 29, "/FictionBook/body/section[2]/table/tr/td")
+    );
+}
+
+#[test]
+fn missing_element_is_located() {
+    let content = BOOK.replace("<book-title>Заголовок</book-title>", "");
+    let error = fb2::from_str(&content).unwrap_err();
+    assert_eq!(
+        location(&error),
+        (4, 9, "/FictionBook/description/title-info")
+    );
+    assert!(error.to_string().starts_with(
+        "invalid FB2 structure at 4:9 /FictionBook/description/title-info: missing field"
+    ));
+}
+
+#[test]
+fn malformed_xml_is_located() {
+    let content = BOOK.replace("<p>More text</p>", "<p>More text</b>");
+    let error = fb2::from_str(&content).unwrap_err();
+    assert!(matches!(error, Error::Syntax(_)), "{error:?}");
+    assert_eq!(location(&error), (13, 30, "/FictionBook/body/section[2]/p"));
+
+    let end = BOOK.find("</section>").unwrap();
+    let error = fb2::from_str(&BOOK[..end]).unwrap_err();
+    assert!(matches!(error, Error::Syntax(_)), "{error:?}");
+    assert_eq!(location(&error), (12, 29, "/FictionBook/body/section"));
+}
+
+#[test]
+fn location_is_the_same_for_every_reader() {
+    let content = BOOK.replace("<book-title>", r#"<book-title xml:lang="?">"#);
+    let full = fb2::from_reader(content.as_bytes()).unwrap_err();
+    let description = fb2::read_description(content.as_bytes()).unwrap_err();
+    let lazy = fb2::from_reader_lazy(content.as_bytes()).unwrap_err();
+    let streamed = BookReader::new(content.as_bytes())
+        .unwrap()
+        .find_map(Result::err)
+        .unwrap();
+    for error in [&description, &lazy, &streamed] {
+        assert_eq!(error.location(), full.location());
+    }
+}
+
+#[test]
+fn streamed_section_error_is_located() {
+    let content = BOOK.replace("<p>More text</p>", r#"<p xml:lang="?">More text</p>"#);
+    let full = fb2::from_str(&content).unwrap_err();
+    assert_eq!(location(&full), (13, 18, "/FictionBook/body/section[2]/p"));
+    let streamed = BookReader::new(Cursor::new(content.as_bytes()))
+        .unwrap()
+        .find_map(Result::err)
+        .unwrap();
+    assert_eq!(streamed.location(), full.location());
+    let items = BookReader::new(BOOK.as_bytes()).unwrap();
+    assert_eq!(
+        items
+            .filter(|item| matches!(item, Ok(BookItem::Section(_))))
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn lazy_error_after_binary_is_located() {
+    let binary = r#"<binary id="cover.png" content-type="image/png">iVBORw0KGgo=</binary>"#;
+    let content = BOOK
+        .replace(binary, "")
+        .replace("<body>", &format!("{binary}\n    <body>"))
+        .replace("<p>More text</p>", "<p>More text</b>");
+    let full = fb2::from_str(&content).unwrap_err();
+    let lazy = fb2::from_reader_lazy(content.as_bytes()).unwrap_err();
+    assert_eq!(lazy.location(), full.location());
+}
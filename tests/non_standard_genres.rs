@@ -1,5 +1,3 @@
-use chrono::NaiveDate;
-
 use fb2::*;
 
 use crate::common::compare;
@@ -73,6 +71,7 @@ fn parse_prose_contemporary_genre() {
                                             },
                                         ),
                                     ],
+                                    extras: Extras::default(),
                                 },
                             ),
                         ],
@@ -87,7 +86,7 @@ fn parse_prose_contemporary_genre() {
                 date: Some(
                     Date {
                         lang: None,
-                        iso_date: NaiveDate::from_ymd_opt(1953, 01, 01),
+                        iso_date: Some(IsoDate::parse("1953-01-01")),
                         display_date: Some("1948-53".into()),
                     },
                 ),
@@ -128,6 +127,7 @@ fn parse_prose_contemporary_genre() {
                     ),
                 ],
                 sequences: vec![],
+                extras: Extras::default(),
             },
             src_title_info: None,
             document_info: Some(DocumentInfo {
@@ -152,7 +152,7 @@ fn parse_prose_contemporary_genre() {
                 ),
                 date: Some(Date {
                     lang: None,
-                    iso_date: NaiveDate::from_ymd_opt(2014, 6, 11),
+                    iso_date: Some(IsoDate::parse("2014-06-11")),
                     display_date: Some("11 June 2014".into()),
                 }),
                 src_urls: vec![
@@ -165,7 +165,7 @@ fn parse_prose_contemporary_genre() {
                     },
                 ),
                 id: Some("a75a6f71-f140-11e3-871d-0025905a0812".into()),
-                version: Some(1.0),
+                version: Some(Version::parse("1.0")),
                 history: Some(
                     Annotation {
                         id: None,
@@ -181,6 +181,7 @@ fn parse_prose_contemporary_genre() {
                                             "v 1.0 – создание fb2 – (On84ly)".into(),
                                         ),
                                     ],
+                                    extras: Extras::default(),
                                 },
                             ),
                         ],
@@ -229,7 +230,7 @@ fn parse_prose_contemporary_genre() {
                         },
                     ),
                     year: Some(
-                        2013,
+                        2013.into(),
                     ),
                     isbn: Some(
                         LocalizedText {
@@ -242,6 +243,7 @@ fn parse_prose_contemporary_genre() {
             ),
             custom_info: vec![],
             output: vec![],
+            extras: Extras::default(),
         },
         bodies: vec![Body {
             name: None,
@@ -261,6 +263,7 @@ fn parse_prose_contemporary_genre() {
                                         "Уинстон Черчилль".into(),
                                     ),
                                 ],
+                                extras: Extras::default(),
                             },
                         ),
                         TitleElement::Paragraph(
@@ -273,6 +276,7 @@ fn parse_prose_contemporary_genre() {
                                         "Вторая мировая война".into(),
                                     ),
                                 ],
+                                extras: Extras::default(),
                             },
                         ),
                     ],
@@ -310,6 +314,7 @@ fn parse_prose_contemporary_genre() {
                                                     },
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -332,6 +337,7 @@ fn parse_prose_contemporary_genre() {
                                                     },
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -354,6 +360,7 @@ fn parse_prose_contemporary_genre() {
                                                     },
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -376,6 +383,7 @@ fn parse_prose_contemporary_genre() {
                                                     },
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -398,6 +406,7 @@ fn parse_prose_contemporary_genre() {
                                                     },
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::EmptyLine,
@@ -411,6 +420,7 @@ fn parse_prose_contemporary_genre() {
                                                     "I: The Gathering Storm © The Estate of Sir Winston S Churchill".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -423,6 +433,7 @@ fn parse_prose_contemporary_genre() {
                                                     "First published by Casell 1948".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -435,6 +446,7 @@ fn parse_prose_contemporary_genre() {
                                                     "II: Their Finest Hour © The Estate of Sir Winston S Churchill".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -447,6 +459,7 @@ fn parse_prose_contemporary_genre() {
                                                     "First published by Casell 1949".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -459,6 +472,7 @@ fn parse_prose_contemporary_genre() {
                                                     "III: The Grand Alliance © The Estate of Sir Winston S Churchill".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -471,6 +485,7 @@ fn parse_prose_contemporary_genre() {
                                                     "First published by Casell 1950".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -483,6 +498,7 @@ fn parse_prose_contemporary_genre() {
                                                     "IV: The Hinge of Fate © The Estate of Sir Winston S Churchill".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -495,6 +511,7 @@ fn parse_prose_contemporary_genre() {
                                                     "First published by Casell 1950".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -507,6 +524,7 @@ fn parse_prose_contemporary_genre() {
                                                     "V: Triumph and Tragedy © The Estate of Sir Winston S Churchill".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -519,6 +537,7 @@ fn parse_prose_contemporary_genre() {
                                                     "First published by Casell 1951".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -531,6 +550,7 @@ fn parse_prose_contemporary_genre() {
                                                     "VI: Closing the Ring © The Estate of Sir Winston S Churchill".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Paragraph(
@@ -543,6 +563,7 @@ fn parse_prose_contemporary_genre() {
                                                     "First published by Casell 1953".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::EmptyLine,
@@ -556,6 +577,7 @@ fn parse_prose_contemporary_genre() {
                                                     "© Издание на русском языке, оформление. «Альпина нон-фикшн», 2010".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::EmptyLine,
@@ -576,6 +598,7 @@ fn parse_prose_contemporary_genre() {
                                                     },
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::EmptyLine,
@@ -585,6 +608,7 @@ fn parse_prose_contemporary_genre() {
                                             lang: None,
                                             style: None,
                                             elements: vec![],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                     SectionPart::Subtitle(
@@ -597,16 +621,57 @@ fn parse_prose_contemporary_genre() {
                                                     "* * *".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                             ],
                             sections: vec![],
                         },
                     ),
+                    extras: Extras::default(),
                 },
             ],
         }],
         binaries: vec![],
+        extras: Extras::default(),
     };
     compare("tests/resources/non_standard_genres.fb2", expected)
 }
+
+#[test]
+fn keep_unknown_genre_codes() {
+    let content = r#"<FictionBook>
+<description><title-info>
+    <genre>sf_litrpg</genre>
+    <genre match="40">network_literature</genre>
+    <genre>sf_history</genre>
+    <genre/>
+    <book-title>Title</book-title>
+</title-info></description>
+<body><section><p>Text</p></section></body>
+</FictionBook>"#;
+    let book = fb2::from_str(content).unwrap();
+    let genres: Vec<_> = book
+        .description
+        .title_info
+        .genres
+        .iter()
+        .map(|genre| genre.value.clone())
+        .collect();
+    assert_eq!(
+        genres,
+        [
+            Genre::Other("sf_litrpg".into()),
+            Genre::Other("network_literature".into()),
+            Genre::SfHistory,
+            Genre::Unrecognised,
+        ]
+    );
+    assert_eq!(genres[1].code(), "network_literature");
+
+    let serialized = quick_xml::se::to_string(&book.description.title_info.genres[1]).unwrap();
+    assert_eq!(
+        serialized,
+        r#"<GenreWithMatch match="40">network_literature</GenreWithMatch>"#
+    );
+}
@@ -1,4 +1,3 @@
-use chrono::NaiveDate;
 use language_tags::LanguageTag;
 
 use fb2::*;
@@ -127,6 +126,7 @@ fn parse_complex() {
                                             },
                                         ),
                                     ],
+                                    extras: Extras::default(),
                                 },
                             ),
                         ],
@@ -141,7 +141,7 @@ fn parse_complex() {
                 date: Some(
                     Date {
                         lang: Some(LanguageTag::parse("en").unwrap()),
-                        iso_date: NaiveDate::from_ymd_opt(1953, 01, 01),
+                        iso_date: Some(IsoDate::parse("1953-01-01")),
                         display_date: Some("1948-53".into()),
                     },
                 ),
@@ -225,14 +225,14 @@ fn parse_complex() {
                     Sequence {
                         name: Some("Книга, чё".into()),
                         number: Some(
-                            1,
+                            1.into(),
                         ),
                         lang: Some(LanguageTag::parse("ru").unwrap()),
                         sequences: vec![
                             Sequence {
                                 name: Some("Два".into()),
                                 number: Some(
-                                    2,
+                                    2.into(),
                                 ),
                                 lang: Some(LanguageTag::parse("ru-RU").unwrap()),
                                 sequences: vec![],
@@ -240,7 +240,7 @@ fn parse_complex() {
                             Sequence {
                                 name: Some("Three".into()),
                                 number: Some(
-                                    3,
+                                    3.into(),
                                 ),
                                 lang: Some(LanguageTag::parse("en-AU").unwrap()),
                                 sequences: vec![],
@@ -250,14 +250,14 @@ fn parse_complex() {
                     Sequence {
                         name: Some("Зачем это нужно".into()),
                         number: Some(
-                            1,
+                            1.into(),
                         ),
                         lang: Some(LanguageTag::parse("ru-KZ").unwrap()),
                         sequences: vec![
                             Sequence {
                                 name: Some("Два".into()),
                                 number: Some(
-                                    2,
+                                    2.into(),
                                 ),
                                 lang: Some(LanguageTag::parse("ru-RU").unwrap()),
                                 sequences: vec![],
@@ -265,7 +265,7 @@ fn parse_complex() {
                             Sequence {
                                 name: Some("Three".into()),
                                 number: Some(
-                                    3,
+                                    3.into(),
                                 ),
                                 lang: Some(LanguageTag::parse("en-AU").unwrap()),
                                 sequences: vec![],
@@ -273,6 +273,7 @@ fn parse_complex() {
                         ],
                     },
                 ],
+                extras: Extras::default(),
             },
             src_title_info: None,
             document_info: Some(DocumentInfo {
@@ -297,7 +298,7 @@ fn parse_complex() {
                 ),
                 date: Some(Date {
                     lang: Some(LanguageTag::parse("en").unwrap()),
-                    iso_date: NaiveDate::from_ymd_opt(2014, 6, 11),
+                    iso_date: Some(IsoDate::parse("2014-06-11")),
                     display_date: Some("11 June 2014".into()),
                 }),
                 src_urls: vec![
@@ -311,7 +312,7 @@ fn parse_complex() {
                     },
                 ),
                 id: Some("a75a6f71-f140-11e3-871d-0025905a0812".into()),
-                version: Some(1.0),
+                version: Some(Version::parse("1.0")),
                 history: Some(
                     Annotation {
                         id: Some(
@@ -333,6 +334,7 @@ fn parse_complex() {
                                             "v 1.0 – создание fb2 – (On84ly)".into(),
                                         ),
                                     ],
+                                    extras: Extras::default(),
                                 },
                             ),
                         ],
@@ -381,7 +383,7 @@ fn parse_complex() {
                         },
                     ),
                     year: Some(
-                        2013,
+                        2013.into(),
                     ),
                     isbn: Some(
                         LocalizedText {
@@ -393,7 +395,7 @@ fn parse_complex() {
                         Sequence {
                             name: Some("Очередная последовательность".into()),
                             number: Some(
-                                6,
+                                6.into(),
                             ),
                             lang: Some(LanguageTag::parse("ru").unwrap()),
                             sequences: vec![],
@@ -401,14 +403,14 @@ fn parse_complex() {
                         Sequence {
                             name: Some("Очередная посредственность".into()),
                             number: Some(
-                                7,
+                                7.into(),
                             ),
                             lang: Some(LanguageTag::parse("ru").unwrap()),
                             sequences: vec![
                                 Sequence {
                                     name: Some("yep".into()),
                                     number: Some(
-                                        8,
+                                        8.into(),
                                     ),
                                     lang: Some(LanguageTag::parse("en").unwrap()),
                                     sequences: vec![],
@@ -416,7 +418,7 @@ fn parse_complex() {
                                 Sequence {
                                     name: Some("nope".into()),
                                     number: Some(
-                                        9,
+                                        9.into(),
                                     ),
                                     lang: Some(LanguageTag::parse("en").unwrap()),
                                     sequences: vec![],
@@ -472,6 +474,7 @@ fn parse_complex() {
                     elements: vec![],
                 },
             ],
+            extras: Extras::default(),
         },
         bodies: vec![
             Body {
@@ -510,6 +513,7 @@ fn parse_complex() {
                                             "Уинстон Черчилль".into(),
                                         ),
                                     ],
+                                    extras: Extras::default(),
                                 },
                             ),
                             TitleElement::EmptyLine,
@@ -536,6 +540,7 @@ fn parse_complex() {
                                             "Content".into(),
                                         ),
                                     ],
+                                    extras: Extras::default(),
                                 },
                             ),
                             EpigraphElement::Poem(
@@ -558,6 +563,7 @@ fn parse_complex() {
                                                                 "Some title".into(),
                                                             ),
                                                         ],
+                                                        extras: Extras::default(),
                                                     },
                                                 ),
                                             ],
@@ -598,6 +604,7 @@ fn parse_complex() {
                                                         "fasdf".into(),
                                                     ),
                                                 ],
+                                                extras: Extras::default(),
                                             },
                                         ),
                                         PoemStanza::Stanza(
@@ -617,6 +624,7 @@ fn parse_complex() {
                                                                             "Танечка".into(),
                                                                         ),
                                                                     ],
+                                                                    extras: Extras::default(),
                                                                 },
                                                             ),
                                                         ],
@@ -636,6 +644,7 @@ fn parse_complex() {
                                                                 "Subtitle".into(),
                                                             ),
                                                         ],
+                                                        extras: Extras::default(),
                                                     },
                                                 ),
                                                 lines: vec![
@@ -652,6 +661,7 @@ fn parse_complex() {
                                                                 "Плач".into(),
                                                             ),
                                                         ],
+                                                        extras: Extras::default(),
                                                     },
                                                     Paragraph {
                                                         id: Some(
@@ -666,6 +676,7 @@ fn parse_complex() {
                                                                 "Мяч".into(),
                                                             ),
                                                         ],
+                                                        extras: Extras::default(),
                                                     },
                                                 ],
                                             },
@@ -685,6 +696,7 @@ fn parse_complex() {
                                                     "Барто".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                         Paragraph {
                                             id: Some(
@@ -699,12 +711,13 @@ fn parse_complex() {
                                                     "Агния".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ],
                                     date: Some(
                                         Date {
                                             lang: Some(LanguageTag::parse("ru").unwrap()),
-                                            iso_date: NaiveDate::from_ymd_opt(10, 10, 21),
+                                            iso_date: Some(IsoDate::parse("0010-10-21")),
                                             display_date: Some("Лохматые".into()),
                                         },
                                     ),
@@ -727,6 +740,7 @@ fn parse_complex() {
                                                         "fasdf".into(),
                                                     ),
                                                 ],
+                                                extras: Extras::default(),
                                             },
                                         ),
                                         CiteElement::Poem(
@@ -750,6 +764,7 @@ fn parse_complex() {
                                                                             "s".into(),
                                                                         ),
                                                                     ],
+                                                                    extras: Extras::default(),
                                                                 },
                                                             ],
                                                             lang: None,
@@ -780,6 +795,7 @@ fn parse_complex() {
                                         "Автор".into(),
                                     ),
                                 ],
+                                extras: Extras::default(),
                             },
                             Paragraph {
                                 id: Some(
@@ -794,6 +810,7 @@ fn parse_complex() {
                                         "Ещё Автор".into(),
                                     ),
                                 ],
+                                extras: Extras::default(),
                             },
                         ],
                     },
@@ -846,6 +863,7 @@ fn parse_complex() {
                                                                             "Parag".into(),
                                                                         ),
                                                                     ],
+                                                                    extras: Extras::default(),
                                                                 },
                                                             ),
                                                             EpigraphElement::Cite(
@@ -875,16 +893,19 @@ fn parse_complex() {
                                                                     "* * *".into(),
                                                                 ),
                                                             ],
+                                                            extras: Extras::default(),
                                                         },
                                                     ),
                                                 ],
                                                 sections: vec![],
                                             },
                                         ),
+                                        extras: Extras::default(),
                                     },
                                 ],
                             },
                         ),
+                        extras: Extras::default(),
                     },
                 ],
             },
@@ -917,12 +938,14 @@ fn parse_complex() {
                                                     "yep".into(),
                                                 ),
                                             ],
+                                            extras: Extras::default(),
                                         },
                                     ),
                                 ],
                                 sections: vec![],
                             },
                         ),
+                        extras: Extras::default(),
                     },
                 ],
             },
@@ -939,6 +962,7 @@ fn parse_complex() {
                 content: "MTIzNAo=".into(),
             },
         ],
+        extras: Extras::default(),
     };
 
     compare("tests/resources/complex.fb2", expected);
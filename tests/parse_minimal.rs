@@ -1,5 +1,3 @@
-use chrono::NaiveDate;
-
 use fb2::*;
 
 use crate::common::compare;
@@ -81,6 +79,7 @@ fn parse_minimal() {
                                             },
                                         ),
                                     ],
+                                    extras: Extras::default(),
                                 },
                             ),
                         ],
@@ -95,7 +94,7 @@ fn parse_minimal() {
                 date: Some(
                     Date {
                         lang: None,
-                        iso_date: NaiveDate::from_ymd_opt(1953, 01, 01),
+                        iso_date: Some(IsoDate::parse("1953-01-01")),
                         display_date: Some("1948-53".into()),
                     },
                 ),
@@ -136,6 +135,7 @@ fn parse_minimal() {
                     ),
                 ],
                 sequences: vec![],
+                extras: Extras::default(),
             },
             src_title_info: None,
             document_info: Some(DocumentInfo {
@@ -160,7 +160,7 @@ fn parse_minimal() {
                 ),
                 date: Some(Date {
                     lang: None,
-                    iso_date: NaiveDate::from_ymd_opt(2014, 6, 11),
+                    iso_date: Some(IsoDate::parse("2014-06-11")),
                     display_date: Some("11 June 2014".into()),
                 }),
                 src_urls: vec![
@@ -173,7 +173,7 @@ fn parse_minimal() {
                     },
                 ),
                 id: Some("a75a6f71-f140-11e3-871d-0025905a0812".into()),
-                version: Some(1.0),
+                version: Some(Version::parse("1.0")),
                 history: Some(
                     Annotation {
                         id: None,
@@ -189,6 +189,7 @@ fn parse_minimal() {
                                             "v 1.0 – создание fb2 – (On84ly)".into(),
                                         ),
                                     ],
+                                    extras: Extras::default(),
                                 },
                             ),
                         ],
@@ -237,7 +238,7 @@ fn parse_minimal() {
                         },
                     ),
                     year: Some(
-                        2013,
+                        2013.into(),
                     ),
                     isbn: Some(
                         LocalizedText {
@@ -250,6 +251,7 @@ fn parse_minimal() {
             ),
             custom_info: vec![],
             output: vec![],
+            extras: Extras::default(),
         },
         bodies: vec![Body {
             name: None,
@@ -269,6 +271,7 @@ fn parse_minimal() {
                                         "Уинстон Черчилль".into(),
                                     ),
                                 ],
+                                extras: Extras::default(),
                             },
                         ),
                         TitleElement::Paragraph(
@@ -281,6 +284,7 @@ fn parse_minimal() {
                                         "Вторая мировая война".into(),
                                     ),
                                 ],
+                                extras: Extras::default(),
                             },
                         ),
                     ],
@@ -318,6 +322,7 @@ fn parse_minimal() {
                                                 },
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -340,6 +345,7 @@ fn parse_minimal() {
                                                 },
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -362,6 +368,7 @@ fn parse_minimal() {
                                                 },
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -384,6 +391,7 @@ fn parse_minimal() {
                                                 },
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -406,6 +414,7 @@ fn parse_minimal() {
                                                 },
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::EmptyLine,
@@ -419,6 +428,7 @@ fn parse_minimal() {
                                                 "I: The Gathering Storm © The Estate of Sir Winston S Churchill".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -431,6 +441,7 @@ fn parse_minimal() {
                                                 "First published by Casell 1948".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -443,6 +454,7 @@ fn parse_minimal() {
                                                 "II: Their Finest Hour © The Estate of Sir Winston S Churchill".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -455,6 +467,7 @@ fn parse_minimal() {
                                                 "First published by Casell 1949".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -467,6 +480,7 @@ fn parse_minimal() {
                                                 "III: The Grand Alliance © The Estate of Sir Winston S Churchill".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -479,6 +493,7 @@ fn parse_minimal() {
                                                 "First published by Casell 1950".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -491,6 +506,7 @@ fn parse_minimal() {
                                                 "IV: The Hinge of Fate © The Estate of Sir Winston S Churchill".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -503,6 +519,7 @@ fn parse_minimal() {
                                                 "First published by Casell 1950".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -515,6 +532,7 @@ fn parse_minimal() {
                                                 "V: Triumph and Tragedy © The Estate of Sir Winston S Churchill".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -527,6 +545,7 @@ fn parse_minimal() {
                                                 "First published by Casell 1951".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -539,6 +558,7 @@ fn parse_minimal() {
                                                 "VI: Closing the Ring © The Estate of Sir Winston S Churchill".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Paragraph(
@@ -551,6 +571,7 @@ fn parse_minimal() {
                                                 "First published by Casell 1953".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::EmptyLine,
@@ -564,6 +585,7 @@ fn parse_minimal() {
                                                 "© Издание на русском языке, оформление. «Альпина нон-фикшн», 2010".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::EmptyLine,
@@ -584,6 +606,7 @@ fn parse_minimal() {
                                                 },
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::EmptyLine,
@@ -593,6 +616,7 @@ fn parse_minimal() {
                                         lang: None,
                                         style: None,
                                         elements: vec![],
+                                        extras: Extras::default(),
                                     },
                                 ),
                                 SectionPart::Subtitle(
@@ -605,16 +629,19 @@ fn parse_minimal() {
                                                 "* * *".into(),
                                             ),
                                         ],
+                                        extras: Extras::default(),
                                     },
                                 ),
                             ],
                             sections: vec![],
                         },
                     ),
+                    extras: Extras::default(),
                 },
             ],
         }],
         binaries: vec![],
+        extras: Extras::default(),
     };
     compare("tests/resources/minimal.fb2", expected)
 }
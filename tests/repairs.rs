@@ -0,0 +1,144 @@
+use fb2::{Error, Repair, RepairAction};
+
+const SLOPPY: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0">
+    <description>
+        <title-info>
+            <genre match="most">sf</genre>
+            <book-title>Title</book-title>
+        </title-info>
+        <document-info>
+            <version>first</version>
+        </document-info>
+    </description>
+    <body>
+        <section>
+            <p>Text</p>
+            <text-author>Author</text-author>
+            <strong>Bold</strong>
+            <p>Before<poem><stanza><v>Line</v></stanza></poem>after</p>
+        </section>
+        <section>
+            <p>Text</p>
+            <body><section><p>Nested</p></section></body>
+        </section>
+        <body name="merged"><section><p>Merged</p></section></body>
+    </body>
+</FictionBook>"#;
+
+fn describe(repairs: &[Repair]) -> Vec<String> {
+    repairs.iter().map(Repair::to_string).collect()
+}
+
+#[test]
+fn repairs_are_reported_in_document_order() {
+    let (book, repairs) = fb2::from_str_with_repairs(SLOPPY).unwrap();
+    assert_eq!(book, fb2::from_str(SLOPPY).unwrap());
+    assert_eq!(
+        describe(&repairs),
+        [
+            "5:20 /FictionBook/description/title-info/genre/@match: invalid value of `@match` ignored",
+            "9:13 /FictionBook/description/document-info/version: invalid value of `version` ignored",
+            "15:13 /FictionBook/body/section[1]/text-author: `text-author` converted to `p`",
+            "16:13 /FictionBook/body/section[1]/strong: `strong` wrapped into `p`",
+            "17:22 /FictionBook/body/section[1]/p[2]/poem: `poem` dropped",
+            "21:13 /FictionBook/body/section[2]/body: `body` dropped",
+            "23:9 /FictionBook/body/body: `body` unwrapped into its parent",
+        ]
+    );
+}
+
+#[test]
+fn repairs_of_poems_and_paragraphs() {
+    let content = r#"<FictionBook>
+<description><title-info><book-title>Title</book-title></title-info></description>
+<body><section>
+Stray text
+<poem><p>Line</p><empty-line/><stanza><v>Line</v></stanza></poem>
+<p>Text <cite><p>Quote</p></cite><table><tr><td>Cell</td></tr></table> text</p>
+</section></body>
+</FictionBook>"#;
+    let (_, repairs) = fb2::from_str_with_repairs(content).unwrap();
+    let summary: Vec<_> = repairs
+        .iter()
+        .map(|repair| (repair.location.path.as_str(), repair.action))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (
+                "/FictionBook/body/section/text()",
+                RepairAction::Wrapped { into: "p" }
+            ),
+            (
+                "/FictionBook/body/section/poem/p",
+                RepairAction::Wrapped { into: "stanza" }
+            ),
+            (
+                "/FictionBook/body/section/poem/empty-line",
+                RepairAction::Converted { into: "subtitle" }
+            ),
+            ("/FictionBook/body/section/p/cite", RepairAction::Dropped),
+            ("/FictionBook/body/section/p/table", RepairAction::Dropped),
+        ]
+    );
+    assert_eq!(repairs[0].element, "text()");
+    assert_eq!(
+        (repairs[0].location.line, repairs[0].location.column),
+        (4, 1)
+    );
+}
+
+#[test]
+fn offsets_refer_to_the_source() {
+    let mut bytes = b"\xEF\xBB\xBF".to_vec();
+    bytes.extend_from_slice(SLOPPY.as_bytes());
+    let (_, repairs) = fb2::from_reader_with_repairs(bytes.as_slice()).unwrap();
+    let strong = repairs
+        .iter()
+        .find(|repair| repair.element == "strong")
+        .unwrap();
+    let offset = strong.location.offset as usize;
+    assert!(bytes[offset..].starts_with(b"<strong>"));
+}
+
+#[test]
+fn valid_book_has_no_repairs() {
+    let content = std::fs::read_to_string("tests/resources/complex.fb2").unwrap();
+    let (book, repairs) = fb2::from_str_with_repairs(&content).unwrap();
+    assert_eq!(book, fb2::from_str(&content).unwrap());
+    assert_eq!(repairs, []);
+}
+
+#[test]
+fn strict_parsing_fails_on_first_nonconforming_node() {
+    let error = fb2::from_str_strict(SLOPPY).unwrap_err();
+    let Error::NotConforming(repair) = &error else {
+        panic!("{error:?}");
+    };
+    assert_eq!(repair.element, "@match");
+    assert_eq!(
+        repair.location.path,
+        "/FictionBook/description/title-info/genre/@match"
+    );
+    assert_eq!(
+        error.to_string(),
+        "5:20 /FictionBook/description/title-info/genre/@match: invalid value of `@match`"
+    );
+
+    let content = SLOPPY
+        .replace(r#" match="most""#, "")
+        .replace("first", "1.0");
+    let error = fb2::from_reader_strict(content.as_bytes()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "15:13 /FictionBook/body/section[1]/text-author: `text-author` is not allowed in its place"
+    );
+}
+
+#[test]
+fn strict_parsing_accepts_valid_book() {
+    let content = std::fs::read_to_string("tests/resources/complex.fb2").unwrap();
+    let book = fb2::from_str_strict(&content).unwrap();
+    assert_eq!(book, fb2::from_str(&content).unwrap());
+}
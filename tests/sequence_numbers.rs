@@ -0,0 +1,85 @@
+use fb2::{Sequence, SequenceNumber, SequenceValue};
+
+fn book_with_number(number: &str) -> String {
+    format!(
+        r#"<FictionBook>
+    <description>
+        <title-info>
+            <book-title>Title</book-title>
+            <sequence name="Series" number="{number}"/>
+        </title-info>
+    </description>
+    <body><section><p>Text</p></section></body>
+</FictionBook>"#
+    )
+}
+
+fn parse_sequence(number: &str) -> Sequence {
+    let book = fb2::from_str(&book_with_number(number)).unwrap();
+    book.description.title_info.sequences[0].clone()
+}
+
+#[test]
+fn tolerant_numbers() {
+    let cases = [
+        ("3", Some(SequenceValue::Whole(3))),
+        (" 3 ", Some(SequenceValue::Whole(3))),
+        ("#3", Some(SequenceValue::Whole(3))),
+        ("IV", Some(SequenceValue::Whole(4))),
+        ("1.5", Some(SequenceValue::Fraction(1500))),
+        ("1,25", Some(SequenceValue::Fraction(1250))),
+        ("2-3", Some(SequenceValue::Range(2, 3))),
+        ("2 – 3", Some(SequenceValue::Range(2, 3))),
+        ("first", None),
+        ("3-2", None),
+        ("99999999999999999.5", None),
+    ];
+    for (raw, value) in cases {
+        let number = parse_sequence(raw).number.unwrap();
+        assert_eq!(number.raw(), raw);
+        assert_eq!(number.value(), value, "{raw}");
+    }
+    assert_eq!(parse_sequence("").number, None);
+}
+
+#[test]
+fn numbers_are_sortable() {
+    let mut numbers: Vec<_> = ["10", "2-3", "1.5", "IV", "1"]
+        .into_iter()
+        .map(SequenceNumber::parse)
+        .collect();
+    numbers.sort_by_key(SequenceNumber::key);
+    let raw: Vec<_> = numbers.iter().map(SequenceNumber::raw).collect();
+    assert_eq!(raw, ["1", "1.5", "2-3", "IV", "10"]);
+    assert_eq!(SequenceNumber::parse("first").key(), None);
+    assert_eq!(SequenceNumber::parse("IV").whole(), Some(4));
+}
+
+#[test]
+fn numbers_from_values() {
+    for (value, raw) in [
+        (SequenceValue::Whole(3), "3"),
+        (SequenceValue::Fraction(1500), "1.5"),
+        (SequenceValue::Fraction(1250), "1.25"),
+        (SequenceValue::Fraction(2000), "2.0"),
+        (SequenceValue::Range(2, 3), "2-3"),
+    ] {
+        let number = SequenceNumber::from(value);
+        assert_eq!(number.raw(), raw);
+        assert_eq!(number, SequenceNumber::parse(raw));
+    }
+}
+
+#[test]
+fn invalid_number_is_kept_raw() {
+    let (book, repairs) = fb2::from_str_with_repairs(&book_with_number("first")).unwrap();
+    let sequence = &book.description.title_info.sequences[0];
+    assert_eq!(sequence.name.as_deref(), Some("Series"));
+    assert_eq!(
+        repairs[0].to_string(),
+        "5:37 /FictionBook/description/title-info/sequence/@number: invalid value of `@number` ignored"
+    );
+    let xml = quick_xml::se::to_string_with_root("sequence", sequence).unwrap();
+    assert_eq!(xml, r#"<sequence name="Series" number="first"/>"#);
+    assert!(fb2::from_str_strict(&book_with_number("first")).is_err());
+}
@@ -0,0 +1,73 @@
+use fb2::{Version, Year};
+
+fn book_with(version: &str, year: &str) -> String {
+    format!(
+        r#"<FictionBook>
+    <description>
+        <title-info>
+            <book-title>Title</book-title>
+        </title-info>
+        <document-info>
+            <version>{version}</version>
+        </document-info>
+        <publish-info>
+            <year>{year}</year>
+        </publish-info>
+    </description>
+    <body><section><p>Text</p></section></body>
+</FictionBook>"#
+    )
+}
+
+fn parse(version: &str, year: &str) -> (Version, Year) {
+    let book = fb2::from_str(&book_with(version, year)).unwrap();
+    let description = book.description;
+    (
+        description.document_info.unwrap().version.unwrap(),
+        description.publish_info.unwrap().year.unwrap(),
+    )
+}
+
+#[test]
+fn values_are_kept_raw() {
+    let (version, year) = parse("1.0.1", "2005 г.");
+    assert_eq!(version.raw(), "1.0.1");
+    assert_eq!(version.parts(), Some([1, 0, 1].as_slice()));
+    assert_eq!(year.raw(), "2005 г.");
+    assert_eq!(year.value(), Some(2005));
+
+    let (version, year) = parse("first", "весна");
+    assert_eq!(version.raw(), "first");
+    assert_eq!(version.parts(), None);
+    assert_eq!(year.raw(), "весна");
+    assert_eq!(year.value(), None);
+
+    let content = book_with("first", "весна");
+    let book = fb2::from_str(&content).unwrap();
+    let document_info = book.description.document_info.unwrap();
+    let xml = quick_xml::se::to_string_with_root("document-info", &document_info).unwrap();
+    assert!(xml.contains("<version>first</version>"), "{xml}");
+    let (_, repairs) = fb2::from_str_with_repairs(&content).unwrap();
+    assert_eq!(repairs.len(), 2);
+}
+
+#[test]
+fn versions_are_compared_by_parts() {
+    let version = Version::parse;
+    assert!(version("1.10") > version("1.1"));
+    assert!(version("1.10") > version("1.9"));
+    assert!(version("2") > version("1.9.9"));
+    assert!(version("1.0") < version("1.0.1"));
+    assert!(version("first") < version("0.1"));
+    assert_ne!(version("1.10"), version("1.1"));
+    assert_eq!(version("v1,1").parts(), Some([1, 1].as_slice()));
+}
+
+#[test]
+fn values_from_numbers() {
+    let version = Version::new(vec![1, 0, 1]);
+    assert_eq!(version.raw(), "1.0.1");
+    assert_eq!(version, Version::parse("1.0.1"));
+    let year = Year::from(2005);
+    assert_eq!((year.raw(), year.value()), ("2005", Some(2005)));
+}
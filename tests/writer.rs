@@ -0,0 +1,86 @@
+use fb2::{LineEnding, WriteOptions};
+
+const BOOK: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">
+    <description>
+        <title-info>
+            <book-title xml:lang="en">Title</book-title>
+            <coverpage><image l:href="#cover.jpg"/></coverpage>
+            <lang>en</lang>
+        </title-info>
+    </description>
+    <body>
+        <section>
+            <p>Some <strong>strong</strong> <emphasis>text</emphasis> with a <a l:href="#n1" type="note">note</a></p>
+        </section>
+    </body>
+</FictionBook>"##;
+
+#[test]
+fn document_is_namespaced() {
+    let book = fb2::from_str(BOOK).unwrap();
+    let xml = fb2::to_string(&book).unwrap();
+    assert!(
+        xml.starts_with(&format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<FictionBook xmlns=\"{}\" xmlns:l=\"{}\">",
+            fb2::FB2_NAMESPACE,
+            fb2::XLINK_NAMESPACE
+        )),
+        "{xml}"
+    );
+    assert!(
+        xml.contains(r#"<book-title xml:lang="en">Title</book-title>"#),
+        "{xml}"
+    );
+    assert!(xml.contains(r##"<image l:href="#cover.jpg"/>"##), "{xml}");
+    assert!(
+        xml.contains(r##"<a l:href="#n1" type="note">note</a>"##),
+        "{xml}"
+    );
+    assert_eq!(fb2::from_str(&xml).unwrap(), book);
+}
+
+#[test]
+fn indentation_keeps_the_text() {
+    let book = fb2::from_str(BOOK).unwrap();
+    let options = WriteOptions::default()
+        .indent("  ")
+        .line_ending(LineEnding::CrLf);
+    let mut bytes = vec![];
+    fb2::to_writer_with_options(&book, &mut bytes, &options).unwrap();
+    let xml = String::from_utf8(bytes).unwrap();
+    let expected = [
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        r#"<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" xmlns:l="http://www.w3.org/1999/xlink">"#,
+        r#"  <description>"#,
+        r#"    <title-info>"#,
+        r#"      <genre>unrecognised</genre>"#,
+        r#"      <book-title xml:lang="en">Title</book-title>"#,
+        r#"      <coverpage>"#,
+        r##"        <image l:href="#cover.jpg"/>"##,
+        r#"      </coverpage>"#,
+        r#"      <lang>en</lang>"#,
+        r#"    </title-info>"#,
+        r#"  </description>"#,
+        r#"  <body>"#,
+        r#"    <section>"#,
+        r##"      <p>Some <strong>strong</strong><emphasis> text</emphasis> with a <a l:href="#n1" type="note">note</a></p>"##,
+        r#"    </section>"#,
+        r#"  </body>"#,
+        r#"</FictionBook>"#,
+    ]
+    .join("\r\n");
+    assert_eq!(xml, expected);
+    assert_eq!(fb2::from_str(&xml).unwrap(), book);
+}
+
+#[test]
+fn complex_book_is_written_back() {
+    let content = std::fs::read_to_string("tests/resources/complex.fb2").unwrap();
+    let book = fb2::from_str(&content).unwrap();
+    let options = WriteOptions::default().indent("\t");
+    let mut bytes = vec![];
+    fb2::to_writer_with_options(&book, &mut bytes, &options).unwrap();
+    let read_back = fb2::from_slice(&bytes).unwrap();
+    assert_eq!(read_back, book);
+}
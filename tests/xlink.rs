@@ -0,0 +1,118 @@
+use fb2::{Extras, FictionBook, SectionPart, StyleElement};
+
+fn book_with(root_attributes: &str, cover: &str, paragraph: &str) -> String {
+    format!(
+        r#"<FictionBook xmlns="http://www.gribuser.ru/xml/fictionbook/2.0" {root_attributes}>
+    <description>
+        <title-info>
+            <book-title>Title</book-title>
+            <coverpage>{cover}</coverpage>
+        </title-info>
+    </description>
+    <body><section><p>{paragraph}</p></section></body>
+</FictionBook>"#
+    )
+}
+
+fn cover_href(book: &FictionBook) -> Option<&str> {
+    let covers = book.description.title_info.cover_page.as_ref().unwrap();
+    covers.images[0].href.as_deref()
+}
+
+fn link(book: &FictionBook) -> &fb2::Link {
+    let content = &book.bodies[0].sections[0].content.as_ref().unwrap().content;
+    let SectionPart::Paragraph(paragraph) = &content[0] else {
+        panic!("{content:?}");
+    };
+    paragraph
+        .elements
+        .iter()
+        .find_map(|element| match element {
+            StyleElement::Link(link) => Some(link),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn href_of_any_prefix() {
+    for (root, cover) in [
+        (
+            r#"xmlns:l="http://www.w3.org/1999/xlink""#,
+            r##"<image l:href="#cover.jpg"/>"##,
+        ),
+        (
+            r#"xmlns:xlink="http://www.w3.org/1999/xlink""#,
+            r##"<image xlink:href="#cover.jpg"/>"##,
+        ),
+        (
+            "",
+            r##"<image xmlns:x="http://www.w3.org/1999/xlink" x:href="#cover.jpg"/>"##,
+        ),
+        // undeclared prefix
+        ("", r##"<image l:href="#cover.jpg"/>"##),
+        ("", r##"<image href="#cover.jpg"/>"##),
+    ] {
+        let book = fb2::from_str(&book_with(root, cover, "Text")).unwrap();
+        assert_eq!(cover_href(&book), Some("#cover.jpg"), "{cover}");
+        assert!(book.extras.is_empty(), "{root}");
+    }
+}
+
+#[test]
+fn href_is_resolved_by_namespace() {
+    let root = r#"xmlns:xlink="http://www.w3.org/1999/xlink" xmlns:l="urn:vendor""#;
+    let cover = r##"<image l:href="#other.jpg" href="#plain.jpg" xlink:href="#cover.jpg" xlink:type="simple"/>"##;
+    let paragraph =
+        r##"<a l:href="#other" xlink:href="#n1" xlink:type="simple" type="note">1</a>"##;
+    let book = fb2::from_str(&book_with(root, cover, paragraph)).unwrap();
+    assert_eq!(cover_href(&book), Some("#cover.jpg"));
+    let link = link(&book);
+    assert_eq!(link.href.as_deref(), Some("#n1"));
+    assert_eq!(link.kind.as_deref(), Some("note"));
+
+    let cover = r##"<image href="#plain.jpg" l:href="#other.jpg"/>"##;
+    let book = fb2::from_str(&book_with(root, cover, "Text")).unwrap();
+    assert_eq!(cover_href(&book), Some("#plain.jpg"));
+
+    // names are found in the tag, not in the values
+    let cover =
+        "<image alt='l:href=\"#alt.jpg\"'\n    l:href = \"#other.jpg\" xlink:href=\"#cover.jpg\"/>";
+    let book = fb2::from_str(&book_with(root, cover, "Text")).unwrap();
+    assert_eq!(cover_href(&book), Some("#cover.jpg"));
+    let covers = book.description.title_info.cover_page.as_ref().unwrap();
+    assert_eq!(
+        covers.images[0].alt.as_deref(),
+        Some(r##"l:href="#alt.jpg""##)
+    );
+}
+
+#[test]
+fn href_is_written_with_a_free_prefix() {
+    let cover = r##"<image l:href="#cover.jpg"/>"##;
+    let content = book_with(r#"xmlns:l="http://www.w3.org/1999/xlink""#, cover, "Text");
+    let book = fb2::from_str(&content).unwrap();
+    let xml = fb2::to_string(&book).unwrap();
+    assert!(
+        xml.contains(r#"xmlns:l="http://www.w3.org/1999/xlink""#),
+        "{xml}"
+    );
+    assert!(xml.contains(r##"<image l:href="#cover.jpg"/>"##), "{xml}");
+
+    // `l` is bound to another namespace by the book
+    let mut book = book;
+    book.extras = Extras {
+        attributes: vec![("xmlns:l".into(), "urn:vendor".into())],
+        ..Extras::default()
+    };
+    let xml = fb2::to_string(&book).unwrap();
+    assert!(
+        xml.contains(r#"xmlns:xlink="http://www.w3.org/1999/xlink""#),
+        "{xml}"
+    );
+    assert!(
+        xml.contains(r##"<image xlink:href="#cover.jpg"/>"##),
+        "{xml}"
+    );
+    assert_eq!(fb2::from_str(&xml).unwrap(), book);
+}
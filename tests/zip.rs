@@ -0,0 +1,75 @@
+#![cfg(feature = "zip")]
+
+use std::io::{Cursor, Write};
+
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+fn archive(entries: &[(&str, &[u8])]) -> Cursor<Vec<u8>> {
+    let mut writer = ZipWriter::new(Cursor::new(vec![]));
+    for (name, content) in entries {
+        writer.start_file(*name, FileOptions::default()).unwrap();
+        writer.write_all(content).unwrap();
+    }
+    let mut cursor = writer.finish().unwrap();
+    cursor.set_position(0);
+    cursor
+}
+
+#[test]
+fn read_single_book() {
+    let content = std::fs::read("tests/resources/minimal.fb2").unwrap();
+    let archive = archive(&[("readme.txt", b"not a book"), ("book.fb2", &content)]);
+
+    let book = fb2::zip::from_reader(archive).unwrap();
+
+    assert_eq!(book, fb2::from_path("tests/resources/minimal.fb2").unwrap());
+}
+
+#[test]
+fn read_multiple_books() {
+    let minimal = std::fs::read("tests/resources/minimal.fb2").unwrap();
+    let complex = std::fs::read("tests/resources/complex.fb2").unwrap();
+    let archive = archive(&[
+        ("minimal.fb2", &minimal),
+        ("__MACOSX/._minimal.fb2", b"garbage"),
+        ("nested/complex.FB2", &complex),
+    ]);
+
+    let mut archive = fb2::zip::Archive::new(archive).unwrap();
+    assert_eq!(
+        archive.names().collect::<Vec<_>>(),
+        vec!["minimal.fb2", "nested/complex.FB2"]
+    );
+    let books = archive.books().collect::<Result<Vec<_>, _>>().unwrap();
+
+    assert_eq!(
+        books,
+        vec![
+            fb2::from_path("tests/resources/minimal.fb2").unwrap(),
+            fb2::from_path("tests/resources/complex.fb2").unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn archive_without_books() {
+    let archive = archive(&[("readme.txt", b"not a book")]);
+
+    let error = fb2::zip::from_reader(archive).unwrap_err();
+
+    assert!(matches!(error, fb2::Error::Archive(_)), "{error:?}");
+}
+
+#[test]
+fn write_and_read_back() {
+    let book = fb2::from_path("tests/resources/complex.fb2").unwrap();
+    let mut cursor = Cursor::new(vec![]);
+
+    fb2::zip::to_writer(&book, "complex.fb2", &mut cursor).unwrap();
+
+    cursor.set_position(0);
+    let mut archive = fb2::zip::Archive::new(cursor).unwrap();
+    assert_eq!(archive.names().collect::<Vec<_>>(), vec!["complex.fb2"]);
+    assert_eq!(archive.book(0).unwrap(), book);
+}